@@ -0,0 +1,88 @@
+use std::env;
+use std::process::{self, ExitCode};
+
+const PUZZLES: [util::Puzzle; 19] = [
+    day_01::PUZZLE,
+    day_02::PUZZLE,
+    day_03::PUZZLE,
+    day_04::PUZZLE,
+    day_05::PUZZLE,
+    day_06::PUZZLE,
+    day_07::PUZZLE,
+    day_08::PUZZLE,
+    day_09::PUZZLE,
+    day_10::PUZZLE,
+    day_11::PUZZLE,
+    day_12::PUZZLE,
+    day_13::PUZZLE,
+    day_14::PUZZLE,
+    day_15::PUZZLE,
+    day_16::PUZZLE,
+    day_17::PUZZLE,
+    day_18::PUZZLE,
+    day_21::PUZZLE,
+];
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: aoc run --day <N> {}\n       aoc run --all {}",
+        util::Options::USAGE,
+        util::Options::USAGE.trim_end_matches(" [FILE...]")
+    );
+    process::exit(2);
+}
+
+fn find_puzzle(day: &str) -> util::Puzzle {
+    let day: u32 = day.parse().unwrap_or_else(|_| usage());
+    *PUZZLES
+        .iter()
+        .find(|puzzle| puzzle.day == day)
+        .unwrap_or_else(|| {
+            eprintln!("No solution registered for day {}", day);
+            process::exit(2);
+        })
+}
+
+fn run(puzzle: &util::Puzzle, files: &[String], options: &util::Options) -> bool {
+    if options.format == util::Format::Text {
+        println!("Day {}", puzzle.day);
+    }
+    if options.example {
+        util::execute_examples(*puzzle, options)
+    } else if files.is_empty() {
+        util::execute_files([puzzle.input.to_string()], *puzzle, options)
+    } else {
+        util::execute_files(files.iter().cloned(), *puzzle, options)
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some("run") {
+        usage();
+    }
+    let (puzzles, rest) = match args.get(1).map(String::as_str) {
+        Some("--all") => (PUZZLES.to_vec(), &args[2..]),
+        Some("--day") => {
+            let puzzle = find_puzzle(args.get(2).unwrap_or_else(|| usage()));
+            (vec![puzzle], &args[3..])
+        }
+        _ => usage(),
+    };
+    let (options, files) = util::Options::parse(rest.iter().cloned()).unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        usage()
+    });
+    if puzzles.len() > 1 && !files.is_empty() {
+        usage();
+    }
+    let mut success = true;
+    for puzzle in puzzles.iter() {
+        success &= run(puzzle, &files, &options);
+    }
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
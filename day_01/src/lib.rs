@@ -0,0 +1,106 @@
+use util::Error;
+
+pub fn parse(content: &str) -> Result<Vec<i32>, Error> {
+    let lines: Vec<Option<i32>> = util::parse_lines(content, |line| {
+        if line.is_empty() {
+            Ok(None)
+        } else {
+            util::parse_value(line).map(Some)
+        }
+    })?;
+    Ok(lines
+        .rsplit(Option::is_none)
+        .map(|x| x.iter().flatten().sum())
+        .collect())
+}
+
+pub fn part1(elves: &[i32]) -> i32 {
+    *elves.iter().max().unwrap()
+}
+
+pub fn part2(elves: &[i32]) -> i32 {
+    let mut top: [i32; 3] = [0; 3];
+    for &x in elves {
+        let mut smallest_index = 0;
+        let mut smallest_value = top[0];
+        for (n, item) in top.iter().enumerate().skip(1) {
+            if *item < smallest_value {
+                smallest_index = n;
+                smallest_value = *item;
+            }
+        }
+        if smallest_value < x {
+            top[smallest_index] = x;
+        }
+    }
+    top.iter().sum()
+}
+
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000",
+    part1: Some("24000"),
+    part2: Some("45000"),
+}];
+
+pub struct Day01;
+
+impl util::Solution for Day01 {
+    type Input<'a> = Vec<i32>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day01>(1, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let have = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+        assert_eq!(parse(have), Ok(vec![10000, 24000, 11000, 4000, 6000]));
+    }
+
+    #[test]
+    fn reports_invalid_calories() {
+        let error = parse("1000\n\n20x0").unwrap_err();
+        assert_eq!(error.line, Some(3));
+    }
+
+    #[test]
+    fn test_solution() {
+        let elves = vec![6000, 4000, 11000, 24000, 10000];
+        assert_eq!(part1(&elves), 24000);
+        assert_eq!(part2(&elves), 45000);
+    }
+}
@@ -1,4 +1,5 @@
 use core::str::FromStr;
+use util::Error;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Hand {
@@ -24,28 +25,28 @@ fn choose_winning(hand: &Hand) -> Hand {
 }
 
 impl FromStr for Hand {
-    type Err = &'static str;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "A" | "X" => Ok(Hand::Rock),
             "B" | "Y" => Ok(Hand::Paper),
             "C" | "Z" => Ok(Hand::Scissors),
-            _ => Err("Invalid string"),
+            _ => Err(Error::new(format!("Invalid hand {:?}", s))),
         }
     }
 }
 
-fn parse_line(s: &str) -> Result<(Hand, Hand), &'static str> {
+fn parse_line(s: &str) -> Result<(Hand, Hand), Error> {
     let hands: Vec<Hand> = s
         .split_whitespace()
         .take(2)
         .map(str::parse::<Hand>)
-        .collect::<Result<Vec<Hand>, &str>>()?;
+        .collect::<Result<Vec<Hand>, Error>>()?;
     if let [oponent, player] = hands.as_slice() {
         Ok((*oponent, *player))
     } else {
-        Err("Couldn't parse line")
+        Err(Error::new("Expected two hands"))
     }
 }
 
@@ -80,8 +81,8 @@ fn calc_p2_score(oponent: &Hand, player: &Hand) -> i64 {
     }
 }
 
-pub fn parse_file(s: &str) -> Vec<(Hand, Hand)> {
-    s.lines().filter_map(|l| parse_line(l).ok()).collect()
+pub fn parse_file(s: &str) -> Result<Vec<(Hand, Hand)>, Error> {
+    util::parse_lines(s, parse_line)
 }
 
 pub fn part1(game: &[(Hand, Hand)]) -> i64 {
@@ -96,6 +97,40 @@ pub fn part2(game: &[(Hand, Hand)]) -> i64 {
         .sum()
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+A Y
+B X
+C Z",
+    part1: Some("15"),
+    part2: Some("12"),
+}];
+
+pub struct Day02;
+
+impl util::Solution for Day02 {
+    type Input<'a> = Vec<(Hand, Hand)>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse_file(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day02>(2, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -134,12 +169,18 @@ mod tests {
         assert_eq!(parse_line("A Y"), Ok((Hand::Rock, Hand::Paper)));
     }
 
+    #[test]
+    fn reports_invalid_lines() {
+        let error = parse_file("A Y\nB\nC Z").unwrap_err();
+        assert_eq!(error.line, Some(2));
+        let error = parse_file("A Y\nB X\nC W").unwrap_err();
+        assert_eq!(error.line, Some(3));
+    }
+
     #[test]
     fn can_run_game() {
-        let file = "A Y\n\
-            B X\n\
-            C Z\n";
-        let game = parse_file(file);
+        let file = EXAMPLES[0].input;
+        let game = parse_file(file).unwrap();
         assert_eq!(part1(&game), 15);
         assert_eq!(part2(&game), 12);
     }
@@ -1,11 +1,5 @@
-use day_02::{parse_file, part1, part2};
+use std::process::ExitCode;
 
-fn run(file: &str) {
-    let game = parse_file(file);
-    println!("Part 1: {}", part1(&game));
-    println!("Part 2: {}", part2(&game));
-}
-
-fn main() {
-    util::execute(run);
+fn main() -> ExitCode {
+    util::execute(day_02::PUZZLE)
 }
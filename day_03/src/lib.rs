@@ -1,31 +1,64 @@
+use util::Error;
+
+/// Checks that every rucksack holds an even number of items with one of them
+/// in both compartments.
+pub fn parse(s: &str) -> Result<&str, Error> {
+    util::parse_lines(s, |line| {
+        if let Some(n) = line.find(|c: char| !c.is_ascii_alphabetic()) {
+            Err(Error::new("Invalid item").at_column(n + 1))
+        } else if line.len() % 2 != 0 {
+            Err(Error::new("Odd number of items"))
+        } else if find_overlap(line).is_none() {
+            Err(Error::new("No item is in both compartments"))
+        } else {
+            Ok(())
+        }
+    })?;
+    Ok(s)
+}
+
 pub fn part1(s: &str) -> i64 {
-    s.lines().map(find_overlap).map(priority).sum()
+    s.lines()
+        .map(|line| find_overlap(line).expect("parse checks for an overlap"))
+        .map(priority)
+        .sum()
 }
 
-pub fn part2(s: &str) -> i64 {
+/// Fails unless the rucksacks can be split into groups of three sharing an
+/// item.
+pub fn part2(s: &str) -> Result<i64, Error> {
     let lines: Vec<&str> = s.lines().collect();
+    if !lines.len().is_multiple_of(3) {
+        return Err(Error::new("Number of rucksacks is not a multiple of three"));
+    }
     lines
         .chunks(3)
-        .map(|l| find_p2_overlap(l[0], l[1], l[2]))
-        .map(priority)
+        .enumerate()
+        .map(|(n, l)| {
+            find_p2_overlap(l[0], l[1], l[2])
+                .map(priority)
+                .ok_or_else(|| {
+                    Error::new("No item is in all rucksacks of the group").at_line(3 * n + 1)
+                })
+        })
         .sum()
 }
 
-fn find_p2_overlap(x: &str, y: &str, z: &str) -> char {
+fn find_p2_overlap(x: &str, y: &str, z: &str) -> Option<char> {
     let x: Vec<char> = x.chars().collect();
     let y: Vec<char> = y.chars().collect();
     let z: Vec<char> = z.chars().collect();
 
-    *find_overlaps(find_overlaps(x, y), z).first().unwrap()
+    find_overlaps(find_overlaps(x, y), z).first().copied()
 }
 
-fn find_overlap(s: &str) -> char {
+fn find_overlap(s: &str) -> Option<char> {
     let chars: Vec<char> = s.chars().collect();
     let mid = chars.len() / 2;
     let (front, back) = chars.split_at(mid);
-    *find_overlaps(front.to_vec(), back.to_vec())
+    find_overlaps(front.to_vec(), back.to_vec())
         .first()
-        .unwrap()
+        .copied()
 }
 
 fn find_overlaps<T: PartialEq + Copy>(x: Vec<T>, y: Vec<T>) -> Vec<T> {
@@ -33,15 +66,52 @@ fn find_overlaps<T: PartialEq + Copy>(x: Vec<T>, y: Vec<T>) -> Vec<T> {
 }
 
 fn priority(c: char) -> i64 {
-    if ('a'..='z').contains(&c) {
+    if c.is_ascii_lowercase() {
         c as i64 - 'a' as i64 + 1
-    } else if ('A'..='Z').contains(&c) {
+    } else if c.is_ascii_uppercase() {
         c as i64 - 'A' as i64 + 27
     } else {
         0
     }
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw",
+    part1: Some("157"),
+    part2: Some("70"),
+}];
+
+pub struct Day03;
+
+impl util::Solution for Day03 {
+    type Input<'a> = &'a str;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        part2(input)
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day03>(3, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,6 +126,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reports_invalid_rucksacks() {
+        let error = parse("abab\nab1b\nabab").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+        let error = parse("abab\nabb\nabab").unwrap_err();
+        assert_eq!(error.line, Some(2));
+        let error = parse("abab\nabcd\nabab").unwrap_err();
+        assert_eq!(error.line, Some(2));
+        let groups = parse("abab\nabab\nabab\naa\nbb\ncc").unwrap();
+        assert_eq!(part1(groups), 9);
+        assert_eq!(part2(groups).unwrap_err().line, Some(4));
+        assert!(part2(parse("abab\nabab").unwrap()).is_err());
+    }
+
     #[test]
     fn overlaps() {
         let tests = vec![
@@ -67,7 +151,7 @@ mod tests {
             ("CrZsJsPPZsGzwwsLwLmpwMDw", 's'),
         ];
         for (bag, overlap) in tests {
-            assert_eq!(find_overlap(bag), overlap);
+            assert_eq!(find_overlap(bag), Some(overlap));
         }
     }
 }
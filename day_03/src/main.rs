@@ -1,10 +1,5 @@
-use day_03::{part1, part2};
+use std::process::ExitCode;
 
-fn run(file: &str) {
-    println!("Part 1: {}", part1(file));
-    println!("Part 2: {}", part2(file));
-}
-
-fn main() {
-    util::execute(run);
+fn main() -> ExitCode {
+    util::execute(day_03::PUZZLE)
 }
@@ -1,9 +1,9 @@
-use std::str;
+use util::Error;
 
 type Range = (i32, i32);
 
-pub fn parse_file(file: &str) -> Vec<(Range, Range)> {
-    file.lines().map(parse_line).collect()
+pub fn parse_file(file: &str) -> Result<Vec<(Range, Range)>, Error> {
+    util::parse_lines(file, parse_line)
 }
 
 pub fn part1(ranges: &[(Range, Range)]) -> usize {
@@ -29,23 +29,62 @@ fn in_range(x: &i32, (y1, y2): &Range) -> bool {
     *x >= *y1 && *x <= *y2
 }
 
-fn parse_range(range: &str) -> Range {
-    let (x, y) = range.split_once('-').unwrap();
-    (str::parse(x).unwrap(), str::parse(y).unwrap())
+fn parse_range(range: &str) -> Result<Range, Error> {
+    let (x, y) = range
+        .split_once('-')
+        .ok_or_else(|| Error::new(format!("Range {:?} doesn't contain a -", range)))?;
+    Ok((util::parse_value(x)?, util::parse_value(y)?))
 }
 
-fn parse_line(line: &str) -> (Range, Range) {
-    let (e1, e2) = line.split_once(',').unwrap();
-    (parse_range(e1), parse_range(e2))
+fn parse_line(line: &str) -> Result<(Range, Range), Error> {
+    let (e1, e2) = line.split_once(',').ok_or("Line doesn't contain a comma")?;
+    Ok((parse_range(e1)?, parse_range(e2)?))
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8",
+    part1: Some("2"),
+    part2: Some("4"),
+}];
+
+pub struct Day04;
+
+impl util::Solution for Day04 {
+    type Input<'a> = Vec<(Range, Range)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse_file(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day04>(4, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn line_parse() {
-        assert_eq!(parse_line("30-31,2-31"), ((30, 31), (2, 31)));
+        assert_eq!(parse_line("30-31,2-31"), Ok(((30, 31), (2, 31))));
     }
 
     #[test]
@@ -64,7 +103,15 @@ mod tests {
             ((6, 6), (4, 6)),
             ((2, 6), (4, 8)),
         ];
-        assert_eq!(parse_file(have), want);
+        assert_eq!(parse_file(have), Ok(want));
+    }
+
+    #[test]
+    fn reports_invalid_lines() {
+        let error = parse_file("2-4,6-8\n2-3;4-5").unwrap_err();
+        assert_eq!(error.line, Some(2));
+        let error = parse_file("2-4,6-8\n2-3,4-5\n5-x,7-9").unwrap_err();
+        assert_eq!(error.line, Some(3));
     }
 
     #[test]
@@ -1,11 +1,5 @@
-use day_04::{parse_file, part1, part2};
+use std::process::ExitCode;
 
-fn run(file: &str) {
-    let pairs = parse_file(file);
-    println!("Part 1: {}", part1(&pairs));
-    println!("Part 2: {}", part2(&pairs));
-}
-
-fn main() {
-    util::execute(run);
+fn main() -> ExitCode {
+    util::execute(day_04::PUZZLE)
 }
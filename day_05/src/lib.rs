@@ -1,49 +1,70 @@
-fn parse_crates(s: &str) -> Vec<Vec<char>> {
-    let chars: Vec<Vec<char>> = s
-        .lines()
-        .map(|l| l.chars().skip(1).step_by(4).collect())
-        .rev()
-        .collect();
-    let mut crates: Vec<Vec<char>> = Vec::new();
-    for _ in &chars[1] {
-        crates.push(Vec::new());
-    }
-    for row in chars.iter().skip(1) {
-        for (n, c) in row.iter().enumerate() {
-            if *c != ' ' {
-                crates[n].push(*c);
+use util::Error;
+
+fn parse_crates(s: &str) -> Result<Vec<Vec<char>>, Error> {
+    let lines: Vec<&str> = s.lines().collect();
+    let (labels, rows) = lines.split_last().ok_or("No crates found")?;
+    let mut crates: Vec<Vec<char>> = vec![Vec::new(); labels.split_whitespace().count()];
+    for (n, row) in rows.iter().enumerate().rev() {
+        for (i, c) in row.chars().skip(1).step_by(4).enumerate() {
+            if c == ' ' {
+                continue;
             }
+            if !c.is_ascii_uppercase() || i >= crates.len() {
+                return Err(Error::new(format!("Invalid crate {:?}", c))
+                    .at_line(n + 1)
+                    .at_column(4 * i + 2));
+            }
+            crates[i].push(c);
         }
     }
-    crates
+    Ok(crates)
 }
 
 type Command = (i32, i32, i32);
-fn parse_commands(s: &str) -> Vec<Command> {
-    s.lines()
-        .map(|l| {
-            l.split_whitespace()
-                .skip(1)
-                .step_by(2)
-                .filter_map(|n| str::parse(n).ok())
-                .collect::<Vec<i32>>()
-        })
-        .map(|a| {
-            if let [x, y, z] = a.as_slice() {
-                (*x, *y, *z)
-            } else {
-                (0, 0, 0)
-            }
-        })
-        .collect()
+
+fn parse_command(line: &str) -> Result<Command, Error> {
+    match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        ["move", times, "from", from, "to", to] => Ok((
+            util::parse_value(times)?,
+            util::parse_value(from)?,
+            util::parse_value(to)?,
+        )),
+        _ => Err(Error::new("Expected \"move <n> from <stack> to <stack>\"")),
+    }
+}
+
+fn parse_commands(s: &str) -> Result<Vec<Command>, Error> {
+    util::parse_lines(s, parse_command)
 }
 
-pub fn parse(s: &str) -> (Vec<Vec<char>>, Vec<Command>) {
-    if let Some((crates, commands)) = s.split_once("\n\n") {
-        (parse_crates(crates), parse_commands(commands))
-    } else {
-        (vec![], vec![])
+pub fn parse(s: &str) -> Result<(Vec<Vec<char>>, Vec<Command>), Error> {
+    let (crates, commands) = s
+        .split_once("\n\n")
+        .ok_or("Missing empty line between crates and commands")?;
+    let offset = crates.lines().count() + 1;
+    let crates = parse_crates(crates)?;
+    let commands = parse_commands(commands).map_err(|error| error.offset_line(offset))?;
+    let stacks = 1..=crates.len() as i32;
+    // Both parts move the same number of crates, so the heights of the stacks
+    // are enough to check that every move has crates to take.
+    let mut heights: Vec<i32> = crates.iter().map(|stack| stack.len() as i32).collect();
+    for (n, &(times, from, to)) in commands.iter().enumerate() {
+        let line = offset + n + 1;
+        if !stacks.contains(&from) || !stacks.contains(&to) {
+            return Err(Error::new("No such stack").at_line(line));
+        }
+        let (from, to) = (from as usize - 1, to as usize - 1);
+        if times < 0 || times > heights[from] {
+            return Err(Error::new(format!(
+                "Can't move {} crates from a stack of {}",
+                times, heights[from]
+            ))
+            .at_line(line));
+        }
+        heights[from] -= times;
+        heights[to] += times;
     }
+    Ok((crates, commands))
 }
 
 pub fn part1(mut crates: Vec<Vec<char>>, commands: &Vec<Command>) -> String {
@@ -53,7 +74,7 @@ pub fn part1(mut crates: Vec<Vec<char>>, commands: &Vec<Command>) -> String {
             crates[*to as usize - 1].push(tmp);
         }
     }
-    crates.iter().map(|x| x.last().unwrap()).collect()
+    crates.iter().filter_map(|x| x.last()).collect()
 }
 
 pub fn part2(mut crates: Vec<Vec<char>>, commands: &Vec<Command>) -> String {
@@ -64,9 +85,48 @@ pub fn part2(mut crates: Vec<Vec<char>>, commands: &Vec<Command>) -> String {
         let mut tmp = crates[from].split_off(split_point);
         crates[to].append(&mut tmp);
     }
-    crates.iter().map(|x| x.last().unwrap()).collect()
+    crates.iter().filter_map(|x| x.last()).collect()
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2",
+    part1: Some("CMZ"),
+    part2: Some("MCD"),
+}];
+
+pub struct Day05;
+
+impl util::Solution for Day05 {
+    type Input<'a> = (Vec<Vec<char>>, Vec<Command>);
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input.0.clone(), &input.1))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input.0.clone(), &input.1))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day05>(5, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,7 +154,7 @@ mod tests {
             vec!['R', 'D', 'G', 'C', 'P', 'B', 'Q', 'W'],
         ];
 
-        assert_eq!(parse_crates(input), want);
+        assert_eq!(parse_crates(input), Ok(want));
     }
 
     #[test]
@@ -104,7 +164,26 @@ mod tests {
                      move 2 from 2 to 1\n\
                      move 1 from 1 to 2";
         let want = vec![(1, 2, 1), (3, 1, 3), (2, 2, 1), (1, 1, 2)];
-        assert_eq!(parse_commands(input), want);
+        assert_eq!(parse_commands(input), Ok(want));
+    }
+
+    #[test]
+    fn reports_invalid_input() {
+        let tests = vec![
+            ("[A] [B]\n 1   2\n\nmove 1 from 1 to 2\nmove 1 from 2", 5),
+            ("[A] [B]\n 1   2\n\nmove 1 from 1 to 3", 4),
+            ("[A] [b]\n 1   2\n\nmove 1 from 1 to 2", 1),
+            ("[A] [B]\n 1   2\n\nmove 5 from 1 to 2", 4),
+            (
+                "[A] [B]\n 1   2\n\nmove 1 from 1 to 2\nmove 1 from 1 to 2",
+                5,
+            ),
+            ("[A] [B]\n 1   2\n\nmove -1 from 1 to 2", 4),
+        ];
+        for (have, line) in tests {
+            assert_eq!(parse(have).unwrap_err().line, Some(line));
+        }
+        assert!(parse("[A] [B]\n 1   2\nmove 1 from 1 to 2").is_err());
     }
 
     #[test]
@@ -112,6 +191,7 @@ mod tests {
         let crates = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
         let commands = vec![(1, 2, 1), (3, 1, 3), (2, 2, 1), (1, 1, 2)];
         assert_eq!(part1(crates, &commands), "CMZ");
+        assert_eq!(part1(vec![vec!['A'], vec!['B']], &vec![(1, 1, 2)]), "A");
     }
 
     #[test]
@@ -1,3 +1,13 @@
+use util::Error;
+
+pub fn parse(s: &str) -> Result<&str, Error> {
+    let s = s.trim();
+    if let Some(n) = s.find(|c: char| !c.is_ascii_lowercase()) {
+        return Err(Error::new("Invalid character in datastream").at_column(n + 1));
+    }
+    Ok(s)
+}
+
 fn search_start(s: &str, marker_size: usize) -> usize {
     let mut tmp: Vec<char> = Vec::new();
     for (n, c) in s.chars().enumerate() {
@@ -20,10 +30,69 @@ pub fn part2(s: &str) -> usize {
     search_start(s, 14)
 }
 
+/// Examples from the puzzle description, one datastream buffer each.
+pub const EXAMPLES: &[util::Example] = &[
+    util::Example {
+        input: "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+        part1: Some("7"),
+        part2: Some("19"),
+    },
+    util::Example {
+        input: "bvwbjplbgvbhsrlpgdmjqwftvncz",
+        part1: Some("5"),
+        part2: Some("23"),
+    },
+    util::Example {
+        input: "nppdvjthqldpwncqszvftbrmjlhg",
+        part1: Some("6"),
+        part2: Some("23"),
+    },
+    util::Example {
+        input: "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
+        part1: Some("10"),
+        part2: Some("29"),
+    },
+    util::Example {
+        input: "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw",
+        part1: Some("11"),
+        part2: Some("26"),
+    },
+];
+
+pub struct Day06;
+
+impl util::Solution for Day06 {
+    type Input<'a> = &'a str;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day06>(6, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("mjqjpqmgb\n"), Ok("mjqjpqmgb"));
+        assert_eq!(parse("mjq jpqmgb").unwrap_err().column, Some(4));
+    }
+
     #[test]
     fn test_part1() {
         let tests = vec![
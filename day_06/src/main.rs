@@ -1,9 +1,5 @@
-fn run(file: &str) {
-    let file = file.trim();
-    println!("Part 1: {}", day_06::part1(file));
-    println!("Part 2: {}", day_06::part2(file));
-}
+use std::process::ExitCode;
 
-fn main() {
-    util::execute(run);
+fn main() -> ExitCode {
+    util::execute(day_06::PUZZLE)
 }
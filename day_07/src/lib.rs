@@ -1,5 +1,6 @@
 use std::iter;
 use std::str;
+use util::Error;
 
 #[derive(PartialEq, Eq, Debug)]
 enum Command<'a> {
@@ -18,37 +19,39 @@ pub struct File<'a> {
     size: i64,
 }
 
-type SourcePointer<'a> = iter::Peekable<str::Lines<'a>>;
+type SourcePointer<'a> = iter::Peekable<iter::Enumerate<str::Lines<'a>>>;
 
-fn parse_ls<'a>(source: &mut SourcePointer<'a>) -> Vec<FileSystem<'a>> {
+fn parse_ls<'a>(source: &mut SourcePointer<'a>) -> Result<Vec<FileSystem<'a>>, Error> {
     let mut output: Vec<FileSystem> = Vec::new();
-    while source.peek().map_or(false, |&line| !line.starts_with('$')) {
-        let line: Vec<&str> = source.next().unwrap().split_whitespace().collect();
-        output.push(if line[0] == "dir" {
-            FileSystem::Directory(line[1], Vec::new())
-        } else {
-            FileSystem::File(line[1], str::parse(line[0]).unwrap())
-        });
+    while let Some((n, line)) = source.next_if(|(_, line)| !line.starts_with('$')) {
+        let entry = match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["dir", name] => FileSystem::Directory(name, Vec::new()),
+            [size, name] => {
+                FileSystem::File(name, util::parse_value(size).map_err(|e| e.at_line(n + 1))?)
+            }
+            _ => return Err(Error::new("Invalid directory entry").at_line(n + 1)),
+        };
+        output.push(entry);
     }
-    output
+    Ok(output)
 }
 
-fn parse_command<'a>(source: &mut SourcePointer<'a>) -> Command<'a> {
-    let command: Vec<&str> = source.next().unwrap().split_whitespace().collect();
-    match command[1] {
-        "cd" => Command::Cd(command[2]),
-        "ls" => Command::Ls(parse_ls(source)),
-        _ => panic!("Parse error"),
+fn parse_command<'a>(source: &mut SourcePointer<'a>) -> Result<Command<'a>, Error> {
+    let (n, line) = source.next().ok_or("Unexpected end of input")?;
+    match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        ["$", "cd", dir] => Ok(Command::Cd(dir)),
+        ["$", "ls"] => Ok(Command::Ls(parse_ls(source)?)),
+        _ => Err(Error::new(format!("Unknown command {:?}", line)).at_line(n + 1)),
     }
 }
 
-fn parse_commands(s: &str) -> Vec<Command> {
-    let mut source = s.lines().peekable();
+fn parse_commands(s: &str) -> Result<Vec<Command<'_>>, Error> {
+    let mut source = s.lines().enumerate().peekable();
     let mut commands = Vec::new();
-    while source.peek() != None {
-        commands.push(parse_command(&mut source));
+    while source.peek().is_some() {
+        commands.push(parse_command(&mut source)?);
     }
-    commands
+    Ok(commands)
 }
 
 fn change_dir<'a>(dir: &'a str, wd: &mut Vec<&'a str>) {
@@ -87,7 +90,7 @@ fn push_files<'a>(
     }
 }
 
-fn generate_filesystem(commands: Vec<Command>) -> (Vec<Vec<&str>>, Vec<File>) {
+fn generate_filesystem(commands: Vec<Command<'_>>) -> (Vec<Vec<&str>>, Vec<File<'_>>) {
     let mut wd: Vec<&str> = Vec::new();
     let mut directories: Vec<Vec<&str>> = vec![Vec::new()];
     let mut files: Vec<File> = Vec::new();
@@ -100,8 +103,8 @@ fn generate_filesystem(commands: Vec<Command>) -> (Vec<Vec<&str>>, Vec<File>) {
     (directories, files)
 }
 
-pub fn parse(s: &str) -> (Vec<Vec<&str>>, Vec<File>) {
-    generate_filesystem(parse_commands(s))
+pub fn parse(s: &str) -> Result<(Vec<Vec<&str>>, Vec<File<'_>>), Error> {
+    Ok(generate_filesystem(parse_commands(s)?))
 }
 
 fn directory_sizes((directories, files): &(Vec<Vec<&str>>, Vec<File>)) -> Vec<i64> {
@@ -132,6 +135,60 @@ pub fn part2(fs_info: &(Vec<Vec<&str>>, Vec<File>)) -> i64 {
     *sizes.iter().filter(|&&x| x >= space_needed).min().unwrap()
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k",
+    part1: Some("95437"),
+    part2: Some("24933642"),
+}];
+
+pub struct Day07;
+
+impl util::Solution for Day07 {
+    type Input<'a> = (Vec<Vec<&'a str>>, Vec<File<'a>>);
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day07>(7, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,7 +218,20 @@ mod tests {
             8033020 d.log\n\
             5626152 d.ext\n\
             7214296 k";
-        assert_eq!(parse_commands(input), commands());
+        assert_eq!(parse_commands(input), Ok(commands()));
+    }
+
+    #[test]
+    fn reports_invalid_lines() {
+        let tests = vec![
+            ("$ cd /\n$ ls\ndir a\n12x b.txt", 4),
+            ("$ cd /\n$ ls\ndir\n", 3),
+            ("$ cd /\n$ rm a", 2),
+            ("$ cd /\n$ cd", 2),
+        ];
+        for (have, line) in tests {
+            assert_eq!(parse_commands(have).unwrap_err().line, Some(line));
+        }
     }
 
     #[test]
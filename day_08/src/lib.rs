@@ -1,118 +1,120 @@
-mod map;
-
-use map::Map;
-
-pub fn parse(s: &str) -> Map<char> {
-    let lines: Vec<&str> = s.lines().collect();
-    let width = lines[0].len();
-    let height = lines.len();
-    let values = lines
-        .iter()
-        .flat_map(|l| l.chars().collect::<Vec<char>>())
-        .collect();
-    Map {
-        width,
-        height,
-        values,
-    }
+use util::vec2d::{Offset, Point, Vec2d};
+use util::Error;
+
+pub fn parse(s: &str) -> Result<Vec2d<char>, Error> {
+    Vec2d::from_str_grid(s, |c| {
+        if c.is_ascii_digit() {
+            Ok(c)
+        } else {
+            Err("Tree height must be a digit")
+        }
+    })
 }
 
-pub fn part1(map: &Map<char>) -> i64 {
-    let mut trees = Map {
-        width: map.width,
-        height: map.height,
-        values: vec![false; map.values.len()],
-    };
+pub fn part1(map: &Vec2d<char>) -> i64 {
+    let mut trees = Vec2d::new(map.width, map.height, false);
     let mut cur;
     for row in 0..map.height {
         cur = '/';
-        for (col, c) in map.row(row).enumerate() {
+        for (col, &c) in map.row(row).enumerate() {
             if c > cur {
-                trees.set(row, col, true);
+                trees[Point::new(col as i32, row as i32)] = true;
                 cur = c;
             }
         }
         cur = '/';
-        for (col, c) in map.row(row).enumerate().rev() {
+        for (col, &c) in map.row(row).enumerate().rev() {
             if c > cur {
-                trees.set(row, col, true);
+                trees[Point::new(col as i32, row as i32)] = true;
                 cur = c;
             }
         }
     }
     for col in 0..map.width {
         cur = '/';
-        for (row, c) in map.col(col).enumerate() {
+        for (row, &c) in map.col(col).enumerate() {
             if c > cur {
-                trees.set(row, col, true);
+                trees[Point::new(col as i32, row as i32)] = true;
                 cur = c;
             }
         }
         cur = '/';
-        for (row, c) in map.col(col).enumerate().rev() {
+        for (row, &c) in map.col(col).enumerate().rev() {
             if c > cur {
-                trees.set(row, col, true);
+                trees[Point::new(col as i32, row as i32)] = true;
                 cur = c;
             }
         }
     }
-    trees.values.iter().filter(|&&x| x).count() as i64
+    trees.data.iter().filter(|&&x| x).count() as i64
 }
 
-fn scenic_score_at(map: &Map<char>, row: usize, col: usize) -> i64 {
-    let height = map.get(row, col).unwrap();
-    let mut result = 1i64;
-    let mut sub_result = 0;
-    // Check up
-    for current_row in (0..row).rev() {
-        sub_result += 1;
-        if map.get(current_row, col).unwrap() >= height {
+/// Counts the trees seen from `from` looking in `direction`, up to and
+/// including the first one at least as high.
+fn viewing_distance(map: &Vec2d<char>, from: Point, direction: Offset) -> i64 {
+    let height = map[from];
+    let mut distance = 0;
+    let mut point = from + direction;
+    while let Some(&tree) = map.get(point) {
+        distance += 1;
+        if tree >= height {
             break;
         }
+        point += direction;
     }
-    result *= sub_result;
-    sub_result = 0;
-    // Check down
-    for current_row in row + 1..map.height {
-        sub_result += 1;
-        if map.get(current_row, col).unwrap() >= height {
-            break;
-        }
-    }
-    result *= sub_result;
-    sub_result = 0;
-    // Check left
-    for current_col in (0..col).rev() {
-        sub_result += 1;
-        if map.get(row, current_col).unwrap() >= height {
-            break;
-        }
+    distance
+}
+
+fn scenic_score_at(map: &Vec2d<char>, point: Point) -> i64 {
+    Offset::ORTHOGONAL
+        .into_iter()
+        .map(|direction| viewing_distance(map, point, direction))
+        .product()
+}
+
+pub fn part2(map: &Vec2d<char>) -> i64 {
+    map.points()
+        .map(|point| scenic_score_at(map, point))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+30373
+25512
+65332
+33549
+35390",
+    part1: Some("21"),
+    part2: Some("8"),
+}];
+
+pub struct Day08;
+
+impl util::Solution for Day08 {
+    type Input<'a> = Vec2d<char>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
     }
-    result *= sub_result;
-    sub_result = 0;
-    // Check right
-    for current_col in col + 1..map.width {
-        sub_result += 1;
-        if map.get(row, current_col).unwrap() >= height {
-            break;
-        }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
     }
-    result * sub_result
-}
 
-pub fn part2(map: &Map<char>) -> i64 {
-    let mut max = 0i64;
-    for row in 0..map.height {
-        for col in 0..map.width {
-            let scenic_score = scenic_score_at(map, row, col);
-            if scenic_score > max {
-                max = scenic_score;
-            }
-        }
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
     }
-    max
 }
 
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day08>(8, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,41 +126,48 @@ mod tests {
         65332\n\
         33549\n\
         35390";
-        let want = Map {
+        let want = Vec2d {
             width: 5,
             height: 5,
-            values: "3037325512653323354935390".chars().collect(),
+            data: "3037325512653323354935390".chars().collect(),
         };
-        assert_eq!(parse(have), want);
+        assert_eq!(parse(have), Ok(want));
+    }
+
+    #[test]
+    fn reports_invalid_trees() {
+        let error = parse("30373\n25a12").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+        assert!(parse("").is_err());
     }
 
     #[test]
     fn test_part1() {
-        let have = Map {
+        let have = Vec2d {
             width: 5,
             height: 5,
-            values: "3037325512653323354935390".chars().collect(),
+            data: "3037325512653323354935390".chars().collect(),
         };
         assert_eq!(part1(&have), 21);
     }
 
     #[test]
     fn test_part2() {
-        let have = Map {
+        let have = Vec2d {
             width: 5,
             height: 5,
-            values: "3037325512653323354935390".chars().collect(),
+            data: "3037325512653323354935390".chars().collect(),
         };
         assert_eq!(part2(&have), 8);
     }
 
     #[test]
     fn test_scenic_score_at() {
-        let have = Map {
+        let have = Vec2d {
             width: 5,
             height: 5,
-            values: "3037325512653323354935390".chars().collect(),
+            data: "3037325512653323354935390".chars().collect(),
         };
-        assert_eq!(scenic_score_at(&have, 3, 2), 8);
+        assert_eq!(scenic_score_at(&have, Point::new(2, 3)), 8);
     }
 }
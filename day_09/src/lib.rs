@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use util::Error;
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum Direction {
@@ -50,21 +51,23 @@ impl Rope {
     }
 }
 
-pub fn parse(file: &str) -> Vec<Instruction> {
-    file.lines()
-        .map(|line| {
-            let tmp: Vec<&str> = line.split_whitespace().collect();
-            let amount = str::parse(tmp[1]).unwrap();
-            let dir = match tmp[0] {
-                "U" => Direction::Up,
-                "D" => Direction::Down,
-                "L" => Direction::Left,
-                "R" => Direction::Right,
-                _ => panic!("Parse error"),
-            };
-            (dir, amount)
-        })
-        .collect()
+fn parse_line(line: &str) -> Result<Instruction, Error> {
+    let (dir, amount) = line
+        .split_once(' ')
+        .ok_or("Expected direction and amount")?;
+    let dir = match dir {
+        "U" => Direction::Up,
+        "D" => Direction::Down,
+        "L" => Direction::Left,
+        "R" => Direction::Right,
+        _ => return Err(Error::new(format!("Invalid direction {:?}", dir)).at_column(1)),
+    };
+    let amount = util::parse_value(amount).map_err(|e| e.at_column(3))?;
+    Ok((dir, amount))
+}
+
+pub fn parse(file: &str) -> Result<Vec<Instruction>, Error> {
+    util::parse_lines(file, parse_line)
 }
 
 pub fn part1(instructions: &[Instruction]) -> i32 {
@@ -91,6 +94,61 @@ pub fn part2(instructions: &[Instruction]) -> i32 {
     tail_positions.len() as i32
 }
 
+/// Examples from the puzzle description. The second, larger one is given for
+/// part 2 only.
+pub const EXAMPLES: &[util::Example] = &[
+    util::Example {
+        input: "\
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2",
+        part1: Some("13"),
+        part2: Some("1"),
+    },
+    util::Example {
+        input: "\
+R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20",
+        part1: None,
+        part2: Some("36"),
+    },
+];
+
+pub struct Day09;
+
+impl util::Solution for Day09 {
+    type Input<'a> = Vec<Instruction>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day09>(9, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,7 +173,16 @@ mod tests {
             (Direction::Left, 5),
             (Direction::Right, 2),
         ];
-        assert_eq!(parse(input), want);
+        assert_eq!(parse(input), Ok(want));
+    }
+
+    #[test]
+    fn reports_invalid_lines() {
+        let error = parse("R 4\nX 4").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(1)));
+        let error = parse("R 4\nU 4\nL -").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(3), Some(3)));
+        assert_eq!(parse("R4").unwrap_err().line, Some(1));
     }
 
     #[test]
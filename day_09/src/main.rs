@@ -1,9 +1,5 @@
-fn run(file: &str) {
-    let instructions = day_09::parse(file);
-    println!("Part 1: {}", day_09::part1(&instructions));
-    println!("Part 2: {}", day_09::part2(&instructions));
-}
+use std::process::ExitCode;
 
-fn main() {
-    util::execute(run);
+fn main() -> ExitCode {
+    util::execute(day_09::PUZZLE)
 }
@@ -1,20 +1,24 @@
+use std::fmt;
+use util::render;
+use util::vec2d::Vec2d;
+use util::Error;
+
 #[derive(PartialEq, Eq, Debug)]
 pub enum Command {
     Noop,
     AddX(i32),
 }
 
-fn parse_line(s: &str) -> Command {
-    let mut command = s.split_whitespace();
-    match command.next() {
-        Some("noop") => Command::Noop,
-        Some("addx") => Command::AddX(command.next().and_then(|x| str::parse(x).ok()).unwrap()),
-        _ => panic!("Parse error"),
+fn parse_line(s: &str) -> Result<Command, Error> {
+    match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        ["noop"] => Ok(Command::Noop),
+        ["addx", n] => Ok(Command::AddX(util::parse_value(n)?)),
+        _ => Err(Error::new(format!("Unknown instruction {:?}", s))),
     }
 }
 
-pub fn parse(s: &str) -> Vec<Command> {
-    s.lines().map(parse_line).collect()
+pub fn parse(s: &str) -> Result<Vec<Command>, Error> {
+    util::parse_lines(s, parse_line)
 }
 
 pub fn calc_values(commands: &[Command]) -> Vec<i32> {
@@ -33,31 +37,74 @@ pub fn calc_values(commands: &[Command]) -> Vec<i32> {
 }
 
 pub fn part1(values: &[i32]) -> i32 {
-    vec![20, 60, 100, 140, 180, 220]
+    [20, 60, 100, 140, 180, 220]
         .iter()
         .map(|&n| n as i32 * values[n - 1])
         .sum()
 }
 
-pub fn part2(values: &[i32]) -> Vec<String> {
-    let lines: String = values
-        .iter()
-        .enumerate()
-        .map(|(n, &x)| {
-            let n = n as i32 % 40;
-            if n == x - 1 || n == x || n == x + 1 {
-                '#'
-            } else {
-                '.'
-            }
-        })
-        .collect();
-    vec![0, 40, 80, 120, 160, 200]
-        .iter()
-        .map(|&n| String::from(&lines[n..n + 40]))
-        .collect()
+/// The pixels lit on the CRT.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Screen(pub Vec2d<bool>);
+
+impl fmt::Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = render::to_text(&self.0, |&lit| if lit { '#' } else { '.' });
+        write!(f, "{}", text)
+    }
 }
 
+pub fn part2(values: &[i32]) -> Screen {
+    let mut screen = Vec2d::new(40, 6, false);
+    for (point, &x) in screen.points().zip(values) {
+        screen[point] = (point.x() - x).abs() <= 1;
+    }
+    Screen(screen)
+}
+
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: include_str!("../sample"),
+    part1: Some("13140"),
+    part2: Some(
+        "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....",
+    ),
+}];
+
+pub struct Day10;
+
+impl util::Solution for Day10 {
+    type Input<'a> = Vec<i32>;
+    type Answer1 = i32;
+    type Answer2 = Screen;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        let values = calc_values(&parse(s)?);
+        if values.len() < 240 {
+            return Err(Error::new("Program must run for at least 240 cycles"));
+        }
+        Ok(values)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day10>(10, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,7 +117,22 @@ mod tests {
             ("addx -25", Command::AddX(-25)),
         ];
         for (have, want) in tests {
-            assert_eq!(parse_line(have), want);
+            assert_eq!(parse_line(have), Ok(want));
         }
     }
+
+    #[test]
+    fn reports_invalid_lines() {
+        assert_eq!(parse("noop\naddx\nnoop").unwrap_err().line, Some(2));
+        assert_eq!(parse("noop\nnoop\naddx x").unwrap_err().line, Some(3));
+        assert_eq!(parse("jmp 5").unwrap_err().line, Some(1));
+    }
+
+    #[test]
+    fn solve_example() {
+        let example = EXAMPLES[0];
+        let values = calc_values(&parse(example.input).unwrap());
+        assert_eq!(part1(&values).to_string(), example.part1.unwrap());
+        assert_eq!(part2(&values).to_string(), example.part2.unwrap());
+    }
 }
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
+use util::Error;
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Operation {
@@ -9,18 +10,15 @@ pub enum Operation {
 }
 
 impl FromStr for Operation {
-    type Err = &'static str;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pieces: Vec<&str> = s.split_whitespace().skip(1).collect();
-        if pieces[1] == "old" {
-            return Ok(Operation::Square);
-        }
-        let amount = pieces[1].parse().or(Err("Parse Error"))?;
-        match pieces[0] {
-            "*" => Ok(Operation::Times(amount)),
-            "+" => Ok(Operation::Plus(amount)),
-            _ => Err("Parse Error"),
+        match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["old", "*", "old"] => Ok(Operation::Square),
+            ["old", "+", "old"] => Ok(Operation::Times(2)),
+            ["old", "*", n] => Ok(Operation::Times(util::parse_value(n)?)),
+            ["old", "+", n] => Ok(Operation::Plus(util::parse_value(n)?)),
+            _ => Err(Error::new(format!("Unknown operation {:?}", s))),
         }
     }
 }
@@ -76,39 +74,42 @@ pub struct Monkey {
     if_false: usize,
 }
 
+/// Returns the value of the line `n` of a monkey's notes, which must start
+/// with `label`.
+fn field<'a>(lines: &[&'a str], n: usize, label: &str) -> Result<&'a str, Error> {
+    lines
+        .get(n)
+        .and_then(|line| line.trim().strip_prefix(label))
+        .map(str::trim)
+        .ok_or_else(|| Error::new(format!("Expected {:?}", label)).at_line(n + 1))
+}
+
 impl FromStr for Monkey {
-    type Err = &'static str;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.lines().skip(1);
-        let items = iter
-            .next()
-            .ok_or("Parse error")?
-            .split_whitespace()
-            .skip(2)
-            .map(|x| x.trim().trim_end_matches(',').parse().unwrap())
-            .collect();
-        let operation = iter
-            .next()
-            .ok_or("Parse error")?
-            .trim()
-            .trim_start_matches("Operation: new = ")
-            .parse()?;
-        let divisible_by = iter
-            .next()
-            .and_then(|x| x.split_whitespace().last())
-            .and_then(|x| x.parse().ok())
-            .ok_or("Parse error")?;
-        let if_true = iter
-            .next()
-            .and_then(|x| x.split_whitespace().last())
-            .and_then(|x| x.parse().ok())
-            .ok_or("Parse error")?;
-        let if_false = iter
-            .next()
-            .and_then(|x| x.split_whitespace().last())
-            .and_then(|x| x.parse().ok())
-            .ok_or("Parse error")?;
+        let lines: Vec<&str> = s.lines().collect();
+        field(&lines, 0, "Monkey")?;
+        let items = field(&lines, 1, "Starting items:")?
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(util::parse_value)
+            .collect::<Result<VecDeque<i64>, Error>>()
+            .map_err(|e| e.at_line(2))?;
+        let operation = field(&lines, 2, "Operation: new =")?
+            .parse()
+            .map_err(|e: Error| e.at_line(3))?;
+        let divisible_by = util::parse_value(field(&lines, 3, "Test: divisible by")?)
+            .and_then(|n| match n {
+                0 => Err(Error::new("Can't test for divisibility by 0")),
+                n => Ok(n),
+            })
+            .map_err(|e| e.at_line(4))?;
+        let if_true = util::parse_value(field(&lines, 4, "If true: throw to monkey")?)
+            .map_err(|e| e.at_line(5))?;
+        let if_false = util::parse_value(field(&lines, 5, "If false: throw to monkey")?)
+            .map_err(|e| e.at_line(6))?;
 
         Ok(Monkey {
             items,
@@ -120,8 +121,22 @@ impl FromStr for Monkey {
     }
 }
 
-pub fn parse(s: &str) -> Vec<Monkey> {
-    s.split("\n\n").filter_map(|s| s.parse().ok()).collect()
+pub fn parse(s: &str) -> Result<Vec<Monkey>, Error> {
+    let monkeys: Vec<Monkey> = util::parse_blocks(s, str::parse)?;
+    if monkeys.len() < 2 {
+        return Err(Error::new("At least two monkeys are needed"));
+    }
+    for (n, monkey) in monkeys.iter().enumerate() {
+        for target in [monkey.if_true, monkey.if_false] {
+            if target >= monkeys.len() || target == n {
+                return Err(Error::new(format!(
+                    "Monkey {} can't throw to monkey {}",
+                    n, target
+                )));
+            }
+        }
+    }
+    Ok(monkeys)
 }
 
 pub fn part1(mut monkeys: Vec<Monkey>) -> i64 {
@@ -176,6 +191,64 @@ pub fn part2(monkeys: Vec<Monkey>) -> i64 {
     inspections[0] * inspections[1]
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1",
+    part1: Some("10605"),
+    part2: Some("2713310158"),
+}];
+
+pub struct Day11;
+
+impl util::Solution for Day11 {
+    type Input<'a> = Vec<Monkey>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input.clone()))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day11>(11, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,7 +302,28 @@ Monkey 3:
   Test: divisible by 17
     If true: throw to monkey 0
     If false: throw to monkey 1";
-        assert_eq!(parse(have), get_test_monkeys());
+        assert_eq!(parse(have), Ok(get_test_monkeys()));
+    }
+
+    #[test]
+    fn reports_invalid_notes() {
+        let have = "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 54, 65
+  Operation: new = old ^ 6
+  Test: divisible by 19
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+        assert_eq!(parse(have).unwrap_err().line, Some(10));
+        let have = have.replace("^", "+").replace("monkey 1\n", "monkey 2\n");
+        assert!(parse(&have).is_err());
     }
 
     #[test]
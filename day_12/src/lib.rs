@@ -1,179 +1,283 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-use std::ops::{Index, IndexMut};
+use std::fmt;
 use std::str::FromStr;
+use util::vec2d::{Point, Vec2d};
+use util::Error;
+use util::{render, search};
 
-type Coordinate = (usize, usize);
-
-#[derive(PartialEq, Eq, Debug)]
-pub struct Matrix<T> {
-    height: usize,
-    width: usize,
-    values: Vec<T>,
+/// Which steps are allowed on a height map and what they cost. The default
+/// are the rules of the puzzle.
+#[derive(Debug, Clone, Copy)]
+pub struct Rules {
+    /// How many levels a step may go up, or any number with `None`.
+    pub max_climb: Option<i32>,
+    /// How many levels a step may go down, or any number with `None`.
+    pub max_descent: Option<i32>,
+    /// Whether steps may go diagonally.
+    pub diagonal: bool,
+    /// Cost of a step going up by the given number of levels, which is
+    /// negative going down. Steps with a cost of zero or less aren't allowed,
+    /// which keeps routes from going around in circles.
+    pub step_cost: fn(i32) -> i32,
 }
 
-impl<T> Matrix<T> {
-    fn get_neighbors(&self, (x, y): Coordinate) -> Vec<Coordinate> {
-        let mut neighbors = Vec::new();
-        if x > 0 {
-            neighbors.push((x - 1, y));
-        }
-        if y > 0 {
-            neighbors.push((x, y - 1));
-        }
-        if x + 1 < self.height {
-            neighbors.push((x + 1, y));
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            max_climb: Some(1),
+            max_descent: None,
+            diagonal: false,
+            step_cost: |_| 1,
         }
-        if y + 1 < self.width {
-            neighbors.push((x, y + 1));
-        }
-        neighbors
     }
 }
 
-impl<T: Clone> Matrix<T> {
-    fn new(height: usize, width: usize, init: T) -> Matrix<T> {
-        Matrix {
-            height,
-            width,
-            values: vec![init; height * width],
+impl Rules {
+    /// Cost of a step from height `from` to height `to`, if it's allowed.
+    fn cost(&self, from: i32, to: i32) -> Option<i32> {
+        let climb = to - from;
+        if self.max_climb.is_some_and(|max| climb > max)
+            || self.max_descent.is_some_and(|max| -climb > max)
+        {
+            return None;
         }
+        Some((self.step_cost)(climb)).filter(|&cost| cost > 0)
     }
 }
 
-impl Matrix<i32> {
-    fn distance_map(&self, to: Coordinate) -> Matrix<Option<i32>> {
-        let mut distances: Matrix<Option<i32>> = Matrix::new(self.height, self.width, None);
-        distances[to] = Some(0);
-        let mut queue = BinaryHeap::new();
-        queue.push(Field(0, to));
-        while let Some(Field(_, coord)) = queue.pop() {
-            let distance = distances[coord].unwrap();
-            let next_distance = distance + 1;
-            let possible_height = self[coord] - 1;
-            let neighbors = self.get_neighbors(coord);
-            for &neighbor in neighbors.iter() {
-                if self[neighbor] < possible_height {
-                    continue;
-                }
-                if let Some(n) = distances[neighbor] {
-                    if n <= next_distance {
-                        continue;
-                    }
-                }
-                distances[neighbor] = Some(next_distance);
-                queue.push(Field(next_distance, neighbor));
-            }
+#[derive(Debug)]
+pub struct HeightMap {
+    start: Point,
+    goal: Point,
+    map: Vec2d<i32>,
+    rules: Rules,
+}
+
+impl HeightMap {
+    /// Point marked `S`.
+    pub fn start(&self) -> Point {
+        self.start
+    }
+
+    /// Point marked `E`.
+    pub fn goal(&self) -> Point {
+        self.goal
+    }
+
+    pub fn with_rules(self, rules: Rules) -> Self {
+        HeightMap { rules, ..self }
+    }
+
+    /// Points a single step away from `point`, allowed or not.
+    fn around(&self, point: Point) -> Box<dyn Iterator<Item = Point> + '_> {
+        if self.rules.diagonal {
+            Box::new(self.map.adjacent(point))
+        } else {
+            Box::new(self.map.neighbors(point))
         }
-        distances
     }
-}
 
-impl<T> Index<Coordinate> for Matrix<T> {
-    type Output = T;
-    fn index(&self, index: Coordinate) -> &Self::Output {
-        let i = index.0 * self.width + index.1;
-        &self.values[i]
+    /// Cost of a step from `from` to `to`, if the rules allow it.
+    fn step_cost(&self, from: Point, to: Point) -> Option<i32> {
+        self.rules.cost(self.map[from], self.map[to])
     }
-}
 
-impl<T> IndexMut<Coordinate> for Matrix<T> {
-    fn index_mut(&mut self, index: Coordinate) -> &mut Self::Output {
-        let i = index.0 * self.width + index.1;
-        &mut self.values[i]
+    /// All points of the given height, with `a` being 0 and `z` 25.
+    pub fn points_at(&self, height: i32) -> Vec<Point> {
+        self.map
+            .iter()
+            .filter(|&(_, &h)| h == height)
+            .map(|(point, _)| point)
+            .collect()
+    }
+
+    /// Cheapest route from any of `sources` to any of `targets`.
+    pub fn nearest(&self, sources: &[Point], targets: &[Point]) -> Option<Nearest> {
+        let paths = search::dijkstra_from(sources.iter().copied(), |&point| {
+            self.around(point)
+                .filter_map(move |to| Some((to, self.step_cost(point, to)?)))
+        });
+        let (distance, to) = targets
+            .iter()
+            .filter_map(|&to| Some((paths.distance(&to)?, to)))
+            .min()?;
+        let route = paths.path_to(&to)?;
+        Some(Nearest {
+            from: route[0],
+            to,
+            distance,
+        })
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-pub struct HeightMap {
-    start: Coordinate,
-    goal: Coordinate,
-    map: Matrix<i32>,
+/// Closest pair of points found by [`HeightMap::nearest`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Nearest {
+    pub from: Point,
+    pub to: Point,
+    pub distance: i32,
 }
 
 impl FromStr for HeightMap {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines().peekable();
-        let width = lines.peek().ok_or("Parse error")?.len();
-        let height = lines.count();
-        let chars: Vec<char> = s
-            .lines()
-            .flat_map(|line| line.chars().collect::<Vec<char>>())
-            .collect();
-        let start = chars
-            .iter()
-            .enumerate()
-            .find(|(_, &c)| c == 'S')
-            .ok_or("No start found")?
-            .0;
-        let start = (start / width, start % width);
-        let goal = chars
-            .iter()
-            .enumerate()
-            .find(|(_, &c)| c == 'E')
-            .ok_or("No goal found")?
-            .0;
-        let goal = (goal / width, goal % width);
-        let values = chars
-            .iter()
-            .map(|c| match c {
-                'S' => Ok(0),
-                'E' => Ok('z' as i32 - 'a' as i32),
-                'a'..='z' => Ok(*c as i32 - 'a' as i32),
-                _ => Err(format!("Illegal character: {}", c)),
-            })
-            .collect::<Result<Vec<i32>, Self::Err>>()?;
+        let chars = Vec2d::from_str_grid(s, |c| match c {
+            'a'..='z' | 'S' | 'E' => Ok(c),
+            _ => Err(format!("Illegal character: {}", c)),
+        })?;
         Ok(HeightMap {
-            start,
-            goal,
-            map: Matrix {
-                height,
-                width,
-                values,
-            },
+            start: chars.find_unique(&'S')?,
+            goal: chars.find_unique(&'E')?,
+            map: chars.map(|&c| match c {
+                'S' => 0,
+                'E' => 'z' as i32 - 'a' as i32,
+                _ => c as i32 - 'a' as i32,
+            }),
+            rules: Rules::default(),
         })
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-struct Field(i32, Coordinate);
+/// Cheapest cost to get from every point to the goal.
+pub fn generate_distance_map(map: &HeightMap) -> Vec2d<Option<i32>> {
+    let paths = search::dijkstra(map.goal, |&point| {
+        map.around(point)
+            .filter_map(move |from| Some((from, map.step_cost(from, point)?)))
+    });
+    let mut distances = Vec2d::new(map.map.width, map.map.height, None);
+    for (&point, &distance) in paths.distances.iter() {
+        distances[point] = Some(distance);
+    }
+    distances
+}
 
-impl PartialOrd for Field {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// Length of the shortest route to the goal, if there is any.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Distance {
+    Route(i32),
+    NoRoute,
+}
+
+impl From<Option<i32>> for Distance {
+    fn from(distance: Option<i32>) -> Self {
+        distance.map_or(Distance::NoRoute, Distance::Route)
     }
 }
-impl Ord for Field {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.0.cmp(&other.0).reverse().then(self.1.cmp(&other.1))
+
+impl fmt::Display for Distance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Distance::Route(distance) => write!(f, "{}", distance),
+            Distance::NoRoute => write!(f, "no route"),
+        }
     }
 }
 
-pub fn generate_distance_map(map: &HeightMap) -> Matrix<Option<i32>> {
-    map.map.distance_map(map.goal)
+/// Points from which the goal can't be reached.
+pub fn unreachable(distance_map: &Vec2d<Option<i32>>) -> Vec<Point> {
+    distance_map
+        .iter()
+        .filter(|(_, distance)| distance.is_none())
+        .map(|(point, _)| point)
+        .collect()
 }
 
-pub fn part1(map: &HeightMap, distance_map: &Matrix<Option<i32>>) -> i32 {
-    distance_map[map.start].unwrap()
+pub fn part1(map: &HeightMap) -> Distance {
+    generate_distance_map(map)[map.start].into()
 }
 
-pub fn part2(map: &HeightMap, distance_map: &Matrix<Option<i32>>) -> i32 {
-    let mut smallest_distance = i32::max_value();
-    for x in 0..map.map.height {
-        for y in 0..map.map.width {
-            if map.map[(x, y)] != 0 {
-                continue;
-            }
-            if let Some(n) = distance_map[(x, y)] {
-                smallest_distance = smallest_distance.min(n);
-            }
-        }
+pub fn part2(map: &HeightMap) -> Distance {
+    let nearest = map.nearest(&map.points_at(0), &[map.goal]);
+    nearest.map(|nearest| nearest.distance).into()
+}
+
+/// Points of a cheapest route from `from` to the goal, both included, found
+/// by stepping down the distance map.
+pub fn route(
+    map: &HeightMap,
+    distance_map: &Vec2d<Option<i32>>,
+    from: Point,
+) -> Option<Vec<Point>> {
+    let mut point = from;
+    let mut distance = (*distance_map.get(point)?)?;
+    let mut route = vec![point];
+    while point != map.goal {
+        point = map.around(point).find(|&next| {
+            map.step_cost(point, next)
+                .zip(distance_map[next])
+                .is_some_and(|(cost, rest)| cost + rest == distance)
+        })?;
+        distance = distance_map[point]?;
+        route.push(point);
+    }
+    Some(route)
+}
+
+/// Points of a cheapest route from the start to the goal, like [`route`]
+/// from [`HeightMap::start`].
+pub fn route_from_start(map: &HeightMap, distance_map: &Vec2d<Option<i32>>) -> Option<Vec<Point>> {
+    route(map, distance_map, map.start)
+}
+
+/// Draws `route` like the puzzle description does, with an arrow on every
+/// point of the route pointing to the next one and `.` everywhere else.
+/// Diagonal steps get diagonal arrows, and steps to a point that isn't next
+/// to the last one a `?`.
+pub fn draw_route(map: &HeightMap, route: &[Point]) -> String {
+    let mut chars = map.map.map(|_| '.');
+    chars[map.goal] = 'E';
+    for step in route.windows(2) {
+        chars[step[0]] = match (step[1] - step[0]).0 {
+            [0, -1] => '^',
+            [-1, 0] => '<',
+            [1, 0] => '>',
+            [0, 1] => 'v',
+            [-1, -1] => '↖',
+            [1, -1] => '↗',
+            [-1, 1] => '↙',
+            [1, 1] => '↘',
+            _ => '?',
+        };
     }
-    smallest_distance
+    render::to_text(&chars, |&c| c)
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi",
+    part1: Some("31"),
+    part2: Some("29"),
+}];
+
+pub struct Day12;
+
+impl util::Solution for Day12 {
+    type Input<'a> = HeightMap;
+    type Answer1 = Distance;
+    type Answer2 = Distance;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        s.parse()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day12>(12, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,31 +289,161 @@ mod tests {
             accszExk\n\
             acctuvwj\n\
             abdefghi";
-        let want = HeightMap {
-            start: (0, 0),
-            goal: (2, 5),
-            map: Matrix {
-                height: 5,
-                width: 8,
-                values: vec![
-                    0, 0, 1, 16, 15, 14, 13, 12, 0, 1, 2, 17, 24, 23, 23, 11, 0, 2, 2, 18, 25, 25,
-                    23, 10, 0, 2, 2, 19, 20, 21, 22, 9, 0, 1, 3, 4, 5, 6, 7, 8,
-                ],
-            },
+        let want = Vec2d {
+            width: 8,
+            height: 5,
+            data: vec![
+                0, 0, 1, 16, 15, 14, 13, 12, 0, 1, 2, 17, 24, 23, 23, 11, 0, 2, 2, 18, 25, 25, 23,
+                10, 0, 2, 2, 19, 20, 21, 22, 9, 0, 1, 3, 4, 5, 6, 7, 8,
+            ],
         };
-        assert_eq!(have.parse(), Ok(want));
+        let map: HeightMap = have.parse().unwrap();
+        assert_eq!((map.start, map.goal), (Point::new(0, 0), Point::new(5, 2)));
+        assert_eq!(map.map, want);
+        let rules = map.rules;
+        assert_eq!((rules.max_climb, rules.max_descent), (Some(1), None));
+        assert!(!rules.diagonal);
+        assert_eq!((rules.step_cost)(5), 1);
+    }
+
+    #[test]
+    fn reports_invalid_maps() {
+        let error = "Sab\nabE\naBc".parse::<HeightMap>().unwrap_err();
+        assert_eq!((error.line, error.column), (Some(3), Some(2)));
+        let error = "Sab\nSbE".parse::<HeightMap>().unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(1)));
+        assert!("Sab\nabc".parse::<HeightMap>().is_err());
+        let error = "Sabc\nabE".parse::<HeightMap>().unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(4)));
     }
 
     #[test]
     fn test_solution() {
-        let have = "Sabqponm\n\
-            abcryxxl\n\
-            accszExk\n\
-            acctuvwj\n\
-            abdefghi";
+        let have = EXAMPLES[0].input;
         let map = have.parse().unwrap();
+        assert_eq!(part1(&map), Distance::Route(31));
+        assert_eq!(part2(&map), Distance::Route(29));
+    }
+
+    #[test]
+    fn can_find_route() {
+        let map: HeightMap = EXAMPLES[0].input.parse().unwrap();
+        let distance_map = generate_distance_map(&map);
+        let have = route_from_start(&map, &distance_map).unwrap();
+        assert_eq!(have.len(), 32);
+        assert_eq!((have[0], have[31]), (map.start, map.goal));
+        let want = "\
+>>vv<<<<
+..vvv<<^
+..vv>E^^
+..v>>>^^
+..>>>>>^";
+        assert_eq!(draw_route(&map, &have), want);
+        let diagonal = [
+            (0, 4),
+            (1, 3),
+            (2, 4),
+            (3, 3),
+            (4, 2),
+            (3, 1),
+            (2, 2),
+            (7, 4),
+        ];
+        let diagonal = diagonal.map(|(x, y)| Point::new(x, y));
+        let want = "\
+........
+...↙....
+..?.↖E..
+.↘.↗....
+↗.↗.....";
+        assert_eq!(draw_route(&map, &diagonal), want);
+        let start = map.nearest(&map.points_at(0), &[map.goal]).unwrap().from;
+        let have = route(&map, &distance_map, start).unwrap();
+        assert_eq!(have.len(), 30);
+        assert_eq!(route(&map, &distance_map, Point::new(8, 0)), None);
+    }
+
+    #[test]
+    fn reports_unreachable_points() {
+        let map: HeightMap = "Sbcd\nzyEa\nabcq".parse().unwrap();
+        let distance_map = generate_distance_map(&map);
+        assert_eq!(part1(&map), Distance::NoRoute);
+        assert_eq!(part1(&map).to_string(), "no route");
+        assert_eq!(part2(&map), Distance::NoRoute);
+        let want = [0, 1, 2, 3, 7, 8, 9, 10, 11].map(|n| Point::new(n % 4, n / 4));
+        assert_eq!(unreachable(&distance_map), want);
+        let map: HeightMap = EXAMPLES[0].input.parse().unwrap();
+        assert_eq!(unreachable(&generate_distance_map(&map)), []);
+    }
+
+    #[test]
+    fn can_find_nearest_points() {
+        let map: HeightMap = EXAMPLES[0].input.parse().unwrap();
+        let want = Nearest {
+            from: Point::new(0, 4),
+            to: map.goal,
+            distance: 29,
+        };
+        assert_eq!(map.nearest(&map.points_at(0), &[map.goal()]), Some(want));
+        let want = Nearest {
+            from: map.start,
+            to: Point::new(4, 2),
+            distance: 30,
+        };
+        assert_eq!(map.nearest(&[map.start()], &map.points_at(25)), Some(want));
+        let nearest = map.nearest(&[map.goal], &[map.start]).unwrap();
+        assert_eq!(nearest.distance, 7);
+        assert_eq!(map.nearest(&[], &[map.goal]), None);
+        assert_eq!(map.nearest(&[map.start], &[]), None);
+    }
+
+    #[test]
+    fn can_change_rules() {
+        let map: HeightMap = EXAMPLES[0].input.parse().unwrap();
+        let rules = Rules {
+            max_climb: None,
+            ..Rules::default()
+        };
+        let map = map.with_rules(rules);
+        assert_eq!(part1(&map), Distance::Route(7));
+        let map = map.with_rules(Rules {
+            diagonal: true,
+            ..rules
+        });
+        let distance_map = generate_distance_map(&map);
+        assert_eq!(part1(&map), Distance::Route(5));
+        let have = draw_route(&map, &route(&map, &distance_map, map.start).unwrap());
+        assert_eq!(have.matches(['↘', '>']).count(), 5);
+        let map = map.with_rules(Rules {
+            step_cost: |climb| 1 + climb.max(0),
+            ..Rules::default()
+        });
         let distance_map = generate_distance_map(&map);
-        assert_eq!(part1(&map, &distance_map), 31);
-        assert_eq!(part2(&map, &distance_map), 29);
+        assert_eq!(part1(&map), Distance::Route(31 + 25));
+        assert_eq!(route(&map, &distance_map, map.start).unwrap().len(), 32);
+        let map = map.with_rules(Rules {
+            step_cost: |climb| climb.signum(),
+            max_climb: None,
+            ..Rules::default()
+        });
+        assert_eq!(part1(&map), Distance::NoRoute);
+        let map = map.with_rules(Rules {
+            step_cost: |_| 0,
+            ..Rules::default()
+        });
+        assert_eq!(route(&map, &generate_distance_map(&map), map.start), None);
+        let map = map.with_rules(Rules {
+            max_climb: Some(0),
+            ..Rules::default()
+        });
+        assert_eq!(generate_distance_map(&map)[map.start], None);
+        let map = map.with_rules(Rules {
+            max_descent: Some(0),
+            ..Rules::default()
+        });
+        let route = route(&map, &generate_distance_map(&map), map.start).unwrap();
+        assert!(route
+            .windows(2)
+            .all(|step| map.map[step[0]] <= map.map[step[1]]));
     }
 }
@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::iter::Peekable;
 use std::str::{Chars, FromStr};
+use util::Error;
 
 #[derive(Clone, Debug)]
 pub enum Packet {
@@ -57,9 +58,9 @@ impl TryFrom<&mut Peekable<Tokens<'_>>> for Packet {
             Some(Token::Int(n)) => Ok(Packet::Int(n)),
             Some(Token::BracketOpen) => {
                 let mut list = Vec::new();
-                while tokens.peek().map_or(false, |t| *t != Token::BracketClose) {
+                while tokens.peek().is_some_and(|t| *t != Token::BracketClose) {
                     list.push(Packet::try_from(&mut *tokens)?);
-                    if tokens.peek().map_or(true, |t| *t == Token::BracketClose) {
+                    if tokens.peek().is_none_or(|t| *t == Token::BracketClose) {
                         break;
                     }
                     skip(&mut *tokens, Token::Comma)?;
@@ -86,7 +87,12 @@ impl TryFrom<Tokens<'_>> for Packet {
 impl FromStr for Packet {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Packet::try_from(tokens(s))
+        let mut tokens = tokens(s).peekable();
+        let packet = Packet::try_from(&mut tokens)?;
+        match tokens.next() {
+            Some(t) => Err(format!("Unexpected token {:?} after end of packet", t)),
+            None => Ok(packet),
+        }
     }
 }
 
@@ -96,6 +102,7 @@ enum Token {
     BracketClose,
     Comma,
     Int(i32),
+    Invalid(String),
 }
 
 #[derive(Debug, Clone)]
@@ -122,28 +129,35 @@ impl<'a> Iterator for Tokens<'a> {
             }
             '0'..='9' => {
                 let mut number = String::new();
-                while let Some(c) = self.iter.next_if(|c| ('0'..='9').contains(c)) {
+                while let Some(c) = self.iter.next_if(|c: &char| c.is_ascii_digit()) {
                     number.push(c);
                 }
-                number.parse().ok().map(Token::Int)
+                Some(number.parse().map_or(Token::Invalid(number), Token::Int))
             }
-            _ => None,
+            _ => self.iter.next().map(|c| Token::Invalid(c.to_string())),
         }
     }
 }
 
-fn tokens(s: &str) -> Tokens {
+fn tokens(s: &str) -> Tokens<'_> {
     Tokens {
         iter: s.chars().peekable(),
     }
 }
 
-pub fn parse(s: &str) -> Vec<Packet> {
-    s.lines()
-        .filter(|l| !l.is_empty())
-        .map(|l| l.parse())
-        .collect::<Result<Vec<Packet>, String>>()
-        .expect("Parse error")
+pub fn parse(s: &str) -> Result<Vec<Packet>, Error> {
+    let packets: Vec<Option<Packet>> = util::parse_lines(s, |l| {
+        if l.is_empty() {
+            Ok(None)
+        } else {
+            l.parse().map(Some)
+        }
+    })?;
+    let packets: Vec<Packet> = packets.into_iter().flatten().collect();
+    if !packets.len().is_multiple_of(2) {
+        return Err(Error::new("Odd number of packets"));
+    }
+    Ok(packets)
 }
 
 pub fn part1(packets: &[Packet]) -> usize {
@@ -176,6 +190,60 @@ pub fn part2(mut packets: Vec<Packet>) -> usize {
     key
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]",
+    part1: Some("13"),
+    part2: Some("140"),
+}];
+
+pub struct Day13;
+
+impl util::Solution for Day13 {
+    type Input<'a> = Vec<Packet>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input.clone()))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day13>(13, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,31 +277,22 @@ mod tests {
     }
 
     #[test]
-    fn test_part1() {
-        let have = "[1,1,3,1,1]
-[1,1,5,1,1]
-
-[[1],[2,3,4]]
-[[1],4]
-
-[9]
-[[8,7,6]]
-
-[[4,4],4,4]
-[[4,4],4,4,4]
-
-[7,7,7,7]
-[7,7,7]
-
-[]
-[3]
-
-[[[]]]
-[[]]
+    fn reports_invalid_packets() {
+        let tests = vec![
+            ("[1,2]\n[3]\n\n[1,[2]\n[3]", Some(4)),
+            ("[1,2]\n[3]]", Some(2)),
+            ("[1,2]\n[3,a]", Some(2)),
+            ("[1,2]\n[3]\n\n[4]", None),
+        ];
+        for (have, line) in tests {
+            assert_eq!(parse(have).unwrap_err().line, line);
+        }
+    }
 
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]";
-        let data = parse(have);
+    #[test]
+    fn test_part1() {
+        let have = EXAMPLES[0].input;
+        let data = parse(have).unwrap();
         assert_eq!(part1(&data), 13);
         assert_eq!(part2(data), 140);
     }
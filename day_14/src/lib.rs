@@ -1,6 +1,8 @@
-use std::collections::HashSet;
+use std::fmt;
 use std::str::FromStr;
-use util::vec2d::{Offset, Point};
+use util::render;
+use util::vec2d::{GrowingVec2d, Offset, Point};
+use util::Error;
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Line(Vec<Point>);
@@ -8,42 +10,53 @@ pub struct Line(Vec<Point>);
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct CaveMap {
     floor: i32,
-    data: HashSet<Point>,
+    data: GrowingVec2d<bool>,
 }
 
 impl CaveMap {
     fn new() -> Self {
         CaveMap {
             floor: 0,
-            data: HashSet::new(),
+            data: GrowingVec2d::new(false),
         }
     }
 
-    fn draw_section(&mut self, from: Point, to: Point) {
-        if from.0 == to.0 {
-            let a = from.1.min(to.1);
-            let b = from.1.max(to.1);
+    fn draw_section(&mut self, from: Point, to: Point) -> Result<(), Error> {
+        if from.x() == to.x() {
+            let a = from.y().min(to.y());
+            let b = from.y().max(to.y());
             for y in a..=b {
-                self.data.insert(Point(from.0, y));
+                self.data[Point::new(from.x(), y)] = true;
             }
-        } else if from.1 == to.1 {
-            let a = from.0.min(to.0);
-            let b = from.0.max(to.0);
+        } else if from.y() == to.y() {
+            let a = from.x().min(to.x());
+            let b = from.x().max(to.x());
             for x in a..=b {
-                self.data.insert(Point(x, from.1));
+                self.data[Point::new(x, from.y())] = true;
             }
         } else {
-            panic!("Cannot draw diagonal lines");
+            return Err(Error::new("Cannot draw diagonal lines"));
         }
+        Ok(())
     }
 
-    fn draw_line(&mut self, line: &Line) {
+    fn draw_line(&mut self, line: &Line) -> Result<(), Error> {
         let mut iter = line.0.iter();
-        let mut last = *iter.next().unwrap();
+        let mut last = *iter.next().ok_or("Empty line")?;
         for &next in iter {
-            self.draw_section(last, next);
+            self.draw_section(last, next)?;
             last = next;
         }
+        Ok(())
+    }
+}
+
+/// Shows the rock and sand of the map, cut to the area they cover.
+impl fmt::Display for CaveMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cave = self.data.cropped();
+        let text = render::to_text(&cave, |&blocked| if blocked { '#' } else { '.' });
+        write!(f, "{}", text)
     }
 }
 
@@ -58,34 +71,26 @@ impl FromStr for Line {
     }
 }
 
-pub fn parse(s: &str) -> CaveMap {
-    let lines: Vec<Line> = s.lines().map(|s| s.parse().expect("Parse error")).collect();
+pub fn parse(s: &str) -> Result<CaveMap, Error> {
     let mut map = CaveMap::new();
-    for line in lines.iter() {
-        map.draw_line(line);
-    }
-    map.floor = map
-        .data
-        .iter()
-        .map(|point| point.1)
-        .max()
-        .expect("No points in map?!")
-        + 2;
-    map
+    util::parse_lines(s, |s| map.draw_line(&s.parse()?))?;
+    let (_, lowest) = map.data.bounds().ok_or("No rocks in map")?;
+    map.floor = lowest.y() + 2;
+    Ok(map)
 }
 
 pub fn part1(mut map: CaveMap) -> u32 {
     let mut count = 0u32;
     let mut trail = Vec::new();
-    let mut check = Point(500, 0);
-    let offsets = vec![Offset(0, 1), Offset(-1, 1), Offset(1, 1)];
+    let mut check = Point::new(500, 0);
+    let offsets = [Offset::new(0, 1), Offset::new(-1, 1), Offset::new(1, 1)];
     'outer: loop {
         for &offset in offsets.iter() {
             let field = check + offset;
-            if field.1 > map.floor {
+            if field.y() > map.floor {
                 return count;
             }
-            if map.data.contains(&field) {
+            if map.data[field] {
                 continue;
             } else {
                 trail.push(check);
@@ -93,7 +98,7 @@ pub fn part1(mut map: CaveMap) -> u32 {
                 continue 'outer;
             }
         }
-        map.data.insert(check);
+        map.data[check] = true;
         check = trail.pop().expect("This should never happen");
         count += 1;
     }
@@ -102,12 +107,12 @@ pub fn part1(mut map: CaveMap) -> u32 {
 pub fn part2(mut map: CaveMap) -> u32 {
     let mut count = 0u32;
     let mut trail = Vec::new();
-    let mut check = Point(500, 0);
-    let offsets = vec![Offset(0, 1), Offset(-1, 1), Offset(1, 1)];
+    let mut check = Point::new(500, 0);
+    let offsets = [Offset::new(0, 1), Offset::new(-1, 1), Offset::new(1, 1)];
     'outer: loop {
         for &offset in offsets.iter() {
             let field = check + offset;
-            if field.1 >= map.floor || map.data.contains(&field) {
+            if field.y() >= map.floor || map.data[field] {
                 continue;
             } else {
                 trail.push(check);
@@ -115,7 +120,7 @@ pub fn part2(mut map: CaveMap) -> u32 {
                 continue 'outer;
             }
         }
-        map.data.insert(check);
+        map.data[check] = true;
         count += 1;
         if let Some(next) = trail.pop() {
             check = next;
@@ -125,6 +130,39 @@ pub fn part2(mut map: CaveMap) -> u32 {
     }
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9",
+    part1: Some("24"),
+    part2: Some("93"),
+}];
+
+pub struct Day14;
+
+impl util::Solution for Day14 {
+    type Input<'a> = CaveMap;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input.clone()))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input.clone()))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day14>(14, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,23 +170,50 @@ mod tests {
     #[test]
     fn can_parse_line() {
         let have = "498,4 -> 498,6 -> 496,6";
-        let want = Line(vec![Point(498, 4), Point(498, 6), Point(496, 6)]);
+        let want = Line(vec![
+            Point::new(498, 4),
+            Point::new(498, 6),
+            Point::new(496, 6),
+        ]);
         assert_eq!(have.parse(), Ok(want));
     }
 
+    #[test]
+    fn reports_invalid_lines() {
+        let tests = vec![
+            ("498,4 -> 498,6\n503,4 -> 502,5", Some(2)),
+            ("498,4 -> 498;6", Some(1)),
+            ("", None),
+        ];
+        for (have, line) in tests {
+            assert_eq!(parse(have).unwrap_err().line, line);
+        }
+    }
+
+    #[test]
+    fn can_display_map() {
+        let map = parse(EXAMPLES[0].input).unwrap();
+        let want = "\
+....#...##
+....#...#.
+..###...#.
+........#.
+........#.
+#########.";
+        assert_eq!(map.to_string(), want);
+    }
+
     #[test]
     fn test_part1() {
-        let have = "498,4 -> 498,6 -> 496,6\n\
-            503,4 -> 502,4 -> 502,9 -> 494,9";
-        let map = parse(have);
+        let have = EXAMPLES[0].input;
+        let map = parse(have).unwrap();
         assert_eq!(part1(map), 24);
     }
 
     #[test]
     fn test_part2() {
-        let have = "498,4 -> 498,6 -> 496,6\n\
-            503,4 -> 502,4 -> 502,9 -> 494,9";
-        let map = parse(have);
+        let have = EXAMPLES[0].input;
+        let map = parse(have).unwrap();
         assert_eq!(part2(map), 93);
     }
 }
@@ -1,9 +1,5 @@
-fn run(file: &str) {
-    let map = day_14::parse(file);
-    println!("Part 1: {}", day_14::part1(map.clone()));
-    println!("Part 2: {}", day_14::part2(map));
-}
+use std::process::ExitCode;
 
-fn main() {
-    util::execute(run);
+fn main() -> ExitCode {
+    util::execute(day_14::PUZZLE)
 }
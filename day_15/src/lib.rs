@@ -1,10 +1,10 @@
 use std::collections::HashSet;
 use std::str::FromStr;
+use util::vec2d::Point;
+use util::Error;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord)]
 pub struct Range(i32, i32);
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
-pub struct Point(i32, i32);
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Sensor {
     position: Point,
@@ -12,15 +12,9 @@ pub struct Sensor {
     distance: i32,
 }
 
-impl Point {
-    fn distance(self, rhs: Point) -> i32 {
-        (self.0 - rhs.0).abs() + (self.1 - rhs.1).abs()
-    }
-}
-
 impl Sensor {
     fn new(position: Point, beacon: Point) -> Sensor {
-        let distance = position.distance(beacon);
+        let distance = position.manhattan(beacon);
         Sensor {
             position,
             beacon,
@@ -29,20 +23,18 @@ impl Sensor {
     }
 }
 
-impl FromStr for Point {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.split_once(", ").ok_or("String doesn't contain a comma")?;
-        let x = x
-            .strip_prefix("x=")
-            .ok_or("x coordinate not starting with x=")
-            .and_then(|s| s.parse().map_err(|_| "Error parsing x coordinate"))?;
-        let y = y
-            .strip_prefix("y=")
-            .ok_or("y coordinate not starting with y=")
-            .and_then(|s| s.parse().map_err(|_| "Error parsing y coordinate"))?;
-        Ok(Point(x, y))
-    }
+/// Parses a position like `x=2, y=18`.
+fn parse_position(s: &str) -> Result<Point, String> {
+    let (x, y) = s.split_once(", ").ok_or("String doesn't contain a comma")?;
+    let x = x
+        .strip_prefix("x=")
+        .ok_or("x coordinate not starting with x=")
+        .and_then(|s| s.parse().map_err(|_| "Error parsing x coordinate"))?;
+    let y = y
+        .strip_prefix("y=")
+        .ok_or("y coordinate not starting with y=")
+        .and_then(|s| s.parse().map_err(|_| "Error parsing y coordinate"))?;
+    Ok(Point::new(x, y))
 }
 
 impl FromStr for Sensor {
@@ -52,20 +44,23 @@ impl FromStr for Sensor {
             .strip_prefix("Sensor at ")
             .and_then(|s| s.split_once(": closest beacon is at "))
             .ok_or("error splitting input")?;
-        Ok(Sensor::new(position.parse()?, beacon.parse()?))
+        Ok(Sensor::new(
+            parse_position(position)?,
+            parse_position(beacon)?,
+        ))
     }
 }
 
-pub fn parse(s: &str) -> Vec<Sensor> {
-    s.lines().map(|s| s.parse().expect("Parse error")).collect()
+pub fn parse(s: &str) -> Result<Vec<Sensor>, Error> {
+    util::parse_lines(s, str::parse::<Sensor>)
 }
 
 fn get_ranges(sensors: &[Sensor], line: i32) -> Vec<Range> {
     sensors
         .iter()
         .filter_map(|sensor| {
-            let d = sensor.distance - (sensor.position.1 - line).abs();
-            let x = sensor.position.0;
+            let d = sensor.distance - (sensor.position.y() - line).abs();
+            let x = sensor.position.x();
             if d >= 0 {
                 Some(Range(x - d, x + d))
             } else {
@@ -77,7 +72,7 @@ fn get_ranges(sensors: &[Sensor], line: i32) -> Vec<Range> {
 
 fn count_with_overlap(mut ranges: Vec<Range>) -> i32 {
     ranges.sort();
-    let mut check = i32::min_value();
+    let mut check = i32::MIN;
     let mut count = 0;
     for Range(start, end) in ranges {
         let start = start.max(check);
@@ -94,8 +89,8 @@ fn blocked_fields(sensors: &[Sensor], line: i32) -> i32 {
     let blocked = count_with_overlap(ranges);
     let beacons: HashSet<i32> = sensors
         .iter()
-        .filter(|sensor| sensor.beacon.1 == line)
-        .map(|sensor| sensor.beacon.0)
+        .filter(|sensor| sensor.beacon.y() == line)
+        .map(|sensor| sensor.beacon.x())
         .collect();
     blocked - beacons.len() as i32
 }
@@ -104,11 +99,11 @@ fn find_empty(sensors: &[Sensor], max: i32) -> i64 {
     for y in 0..=max {
         let mut x = 0;
         'row: while x <= max {
-            let point = Point(x, y);
+            let point = Point::new(x, y);
             for sensor in sensors {
-                if sensor.position.distance(point) <= sensor.distance {
-                    let d = sensor.distance - (sensor.position.1 - y).abs();
-                    x = sensor.position.0 + d + 1;
+                if sensor.position.manhattan(point) <= sensor.distance {
+                    let d = sensor.distance - (sensor.position.y() - y).abs();
+                    x = sensor.position.x() + d + 1;
                     continue 'row;
                 }
             }
@@ -126,6 +121,78 @@ pub fn part2(sensors: &[Sensor]) -> i64 {
     find_empty(sensors, 4_000_000)
 }
 
+/// Example from the puzzle description. Its answers are for row 10 and a
+/// search area up to 20 rather than the puzzle's row 2000000 and area up to
+/// 4000000, so [`Day15Example`] solves it.
+pub const EXAMPLE: &str = "\
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: EXAMPLE,
+    part1: Some("26"),
+    part2: Some("56000011"),
+}];
+
+pub struct Day15;
+
+impl util::Solution for Day15 {
+    type Input<'a> = Vec<Sensor>;
+    type Answer1 = i32;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+/// Solution for the example, which asks about row 10 and a search area up to
+/// 20.
+pub struct Day15Example;
+
+impl util::Solution for Day15Example {
+    type Input<'a> = Vec<Sensor>;
+    type Answer1 = i32;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(blocked_fields(input, 10))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(find_empty(input, 20))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day15>(15, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES)
+        .with_example_solution::<Day15Example>();
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -134,13 +201,22 @@ mod tests {
     fn can_parse_line() {
         let have = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
         let want = Sensor {
-            position: Point(2, 18),
-            beacon: Point(-2, 15),
+            position: Point::new(2, 18),
+            beacon: Point::new(-2, 15),
             distance: 7,
         };
         assert_eq!(have.parse(), Ok(want));
     }
 
+    #[test]
+    fn reports_invalid_lines() {
+        let have = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n\
+            Sensor at x=9, y=16: closest beacon at x=10, y=16";
+        assert_eq!(parse(have).unwrap_err().line, Some(2));
+        let have = "Sensor at x=2, y=1a: closest beacon is at x=-2, y=15";
+        assert_eq!(parse(have).unwrap_err().line, Some(1));
+    }
+
     #[test]
     fn test_part1_one_line() {
         let tests = vec![
@@ -160,47 +236,19 @@ mod tests {
             ("Sensor at x=20, y=1: closest beacon is at x=15, y=3", 0),
         ];
         for test in tests {
-            assert_eq!(blocked_fields(&parse(test.0), 10), test.1);
+            assert_eq!(blocked_fields(&parse(test.0).unwrap(), 10), test.1);
         }
     }
 
     #[test]
     fn test_part1() {
-        let have = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n\
-            Sensor at x=9, y=16: closest beacon is at x=10, y=16\n\
-            Sensor at x=13, y=2: closest beacon is at x=15, y=3\n\
-            Sensor at x=12, y=14: closest beacon is at x=10, y=16\n\
-            Sensor at x=10, y=20: closest beacon is at x=10, y=16\n\
-            Sensor at x=14, y=17: closest beacon is at x=10, y=16\n\
-            Sensor at x=8, y=7: closest beacon is at x=2, y=10\n\
-            Sensor at x=2, y=0: closest beacon is at x=2, y=10\n\
-            Sensor at x=0, y=11: closest beacon is at x=2, y=10\n\
-            Sensor at x=20, y=14: closest beacon is at x=25, y=17\n\
-            Sensor at x=17, y=20: closest beacon is at x=21, y=22\n\
-            Sensor at x=16, y=7: closest beacon is at x=15, y=3\n\
-            Sensor at x=14, y=3: closest beacon is at x=15, y=3\n\
-            Sensor at x=20, y=1: closest beacon is at x=15, y=3";
-        let data = parse(have);
+        let data = parse(EXAMPLE).unwrap();
         assert_eq!(blocked_fields(&data, 10), 26);
     }
 
     #[test]
     fn test_part2() {
-        let have = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n\
-            Sensor at x=9, y=16: closest beacon is at x=10, y=16\n\
-            Sensor at x=13, y=2: closest beacon is at x=15, y=3\n\
-            Sensor at x=12, y=14: closest beacon is at x=10, y=16\n\
-            Sensor at x=10, y=20: closest beacon is at x=10, y=16\n\
-            Sensor at x=14, y=17: closest beacon is at x=10, y=16\n\
-            Sensor at x=8, y=7: closest beacon is at x=2, y=10\n\
-            Sensor at x=2, y=0: closest beacon is at x=2, y=10\n\
-            Sensor at x=0, y=11: closest beacon is at x=2, y=10\n\
-            Sensor at x=20, y=14: closest beacon is at x=25, y=17\n\
-            Sensor at x=17, y=20: closest beacon is at x=21, y=22\n\
-            Sensor at x=16, y=7: closest beacon is at x=15, y=3\n\
-            Sensor at x=14, y=3: closest beacon is at x=15, y=3\n\
-            Sensor at x=20, y=1: closest beacon is at x=15, y=3";
-        let data = parse(have);
+        let data = parse(EXAMPLE).unwrap();
         assert_eq!(find_empty(&data, 20), 56_000_011);
     }
 
@@ -1,7 +1,9 @@
-use std::cmp::{Ordering, Reverse};
+use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::str::FromStr;
 use symbol::Symbol;
+use util::search;
+use util::Error;
 
 pub type Vertex = Symbol;
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -40,11 +42,20 @@ impl FromStr for Valve {
     }
 }
 
-pub fn parse(s: &str) -> Valves {
-    let valves: HashMap<Vertex, Valve> = s
-        .lines()
-        .map(|s| s.parse().expect("Parse error"))
-        .map(|valve: Valve| (valve.vertex, valve))
+pub fn parse(s: &str) -> Result<Valves, Error> {
+    let valves: Vec<Valve> = util::parse_lines(s, str::parse::<Valve>)?;
+    let names: HashSet<Vertex> = valves.iter().map(|valve| valve.vertex).collect();
+    if !names.contains(&"AA".into()) {
+        return Err(Error::new("There is no valve AA"));
+    }
+    for (n, valve) in valves.iter().enumerate() {
+        if let Some(target) = valve.targets.iter().find(|t| !names.contains(t)) {
+            return Err(Error::new(format!("Unknown valve {}", target)).at_line(n + 1));
+        }
+    }
+    let valves: HashMap<Vertex, Valve> = valves
+        .into_iter()
+        .map(|valve| (valve.vertex, valve))
         .collect();
     let candidates: HashSet<Vertex> = valves
         .values()
@@ -61,11 +72,11 @@ pub fn parse(s: &str) -> Valves {
                 .collect::<Vec<((Vertex, Vertex), i32)>>()
         })
         .collect();
-    Valves {
+    Ok(Valves {
         valves,
         candidates,
         distances,
-    }
+    })
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -121,22 +132,11 @@ impl State {
 }
 
 fn find_shortest_paths(from: Symbol, valves: &HashMap<Vertex, Valve>) -> HashMap<Symbol, i32> {
-    let mut queue = BinaryHeap::new();
-    let mut distances = HashMap::from([(from, 0)]);
-    queue.push((Reverse(0), from));
-    while let Some((_, check)) = queue.pop() {
-        let next_distance = distances[&check] + 1;
-        for target in valves[&check].targets.iter() {
-            if distances.get(target).map_or(true, |&n| n > next_distance) {
-                distances
-                    .entry(*target)
-                    .and_modify(|x| *x = next_distance)
-                    .or_insert(next_distance);
-                queue.push((Reverse(next_distance), *target));
-            }
-        }
-    }
-    distances
+    search::bfs(from, |vertex| valves[vertex].targets.clone())
+        .distances
+        .into_iter()
+        .map(|(vertex, distance)| (vertex, distance as i32))
+        .collect()
 }
 
 fn get_max(start: State, valves: &Valves) -> i32 {
@@ -182,6 +182,47 @@ pub fn part2(valves: &Valves) -> i32 {
     )
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II",
+    part1: Some("1651"),
+    part2: Some("1707"),
+}];
+
+pub struct Day16;
+
+impl util::Solution for Day16 {
+    type Input<'a> = Valves;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day16>(16, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,17 +239,20 @@ mod tests {
     }
 
     fn get_data() -> Valves {
-        let have = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB\n\
-            Valve BB has flow rate=13; tunnels lead to valves CC, AA\n\
-            Valve CC has flow rate=2; tunnels lead to valves DD, BB\n\
-            Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE\n\
-            Valve EE has flow rate=3; tunnels lead to valves FF, DD\n\
-            Valve FF has flow rate=0; tunnels lead to valves EE, GG\n\
-            Valve GG has flow rate=0; tunnels lead to valves FF, HH\n\
-            Valve HH has flow rate=22; tunnel leads to valve GG\n\
-            Valve II has flow rate=0; tunnels lead to valves AA, JJ\n\
-            Valve JJ has flow rate=21; tunnel leads to valve II";
-        parse(have)
+        let have = EXAMPLES[0].input;
+        parse(have).unwrap()
+    }
+
+    #[test]
+    fn reports_invalid_valves() {
+        let have = "Valve AA has flow rate=0; tunnels lead to valves DD, BB\n\
+            Valve BB has flow rate=13; tunnels lead to valves CC, AA";
+        assert_eq!(parse(have).unwrap_err().line, Some(1));
+        let have = "Valve AA has flow rate=0; tunnel leads to valve BB\n\
+            Valve BB has flow rate=x; tunnel leads to valve AA";
+        assert_eq!(parse(have).unwrap_err().line, Some(2));
+        let have = "Valve BB has flow rate=0; tunnel leads to valve BB";
+        assert!(parse(have).is_err());
     }
 
     #[test]
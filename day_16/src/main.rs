@@ -1,9 +1,5 @@
-fn run(file: &str) {
-    let data = day_16::parse(file);
-    println!("Part 1: {}", day_16::part1(&data));
-    println!("Part 2: {}", day_16::part2(&data));
-}
+use std::process::ExitCode;
 
-fn main() {
-    util::execute(run);
+fn main() -> ExitCode {
+    util::execute(day_16::PUZZLE)
 }
@@ -1,7 +1,14 @@
-use std::collections::BTreeSet;
-use std::iter::Cycle;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::ops::{Add, AddAssign};
-use std::slice::Iter;
+use std::path::Path;
+use std::str::FromStr;
+use util::point;
+use util::render;
+use util::vec2d::Vec2d;
+use util::Error;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum Direction {
@@ -9,191 +16,614 @@ pub enum Direction {
     Left,
 }
 
-pub fn parse(s: &str) -> Vec<Direction> {
-    s.trim()
+impl Direction {
+    fn offset(self) -> Offset {
+        match self {
+            Direction::Left => Offset::new(-1, 0),
+            Direction::Right => Offset::new(1, 0),
+        }
+    }
+}
+
+pub fn parse(s: &str) -> Result<Vec<Direction>, Error> {
+    let directions = s
+        .trim()
         .chars()
-        .map(|c| match c {
-            '>' => Direction::Right,
-            '<' => Direction::Left,
-            _ => panic!("Parse error"),
+        .enumerate()
+        .map(|(n, c)| match c {
+            '>' => Ok(Direction::Right),
+            '<' => Ok(Direction::Left),
+            _ => Err(Error::new(format!("Invalid jet {:?}", c)).at_column(n + 1)),
         })
-        .collect()
+        .collect::<Result<Vec<Direction>, Error>>()?;
+    if directions.is_empty() {
+        return Err(Error::new("No jets found"));
+    }
+    Ok(directions)
+}
+
+/// Height of the tower after `rocks` rocks have fallen into the chamber of
+/// the puzzle.
+pub fn height_after(directions: &[Direction], rocks: u64) -> i64 {
+    Chamber::default().height_after(directions, rocks)
 }
 
 pub fn part1(directions: &[Direction]) -> i64 {
-    let shapes = get_shapes();
-    let mut game = Game::new(&shapes, directions);
-    for _ in 0..2022 {
-        game.drop();
-    }
-    game.height()
+    height_after(directions, 2022)
 }
 
 pub fn part2(directions: &[Direction]) -> i64 {
-    let shapes = get_shapes();
-    let mut game = Game::new(&shapes, directions);
-    // for _ in 0..50_000 {
-    //     game.drop();
-    // }
-    game.height()
+    height_after(directions, 1_000_000_000_000)
 }
 
-#[derive(PartialEq, Eq, Debug, PartialOrd, Ord, Clone, Copy, Hash)]
-struct Point(i64, i64);
+/// Position in the chamber, with x being the column and y the row counted
+/// from the floor up.
+pub type Point = point::Point<i64, 2>;
+type Offset = point::Offset<i64, 2>;
 
-impl Add<Self> for Point {
-    type Output = Self;
+/// Shapes of the rocks in the puzzle, in the order they fall.
+pub const SHAPES: &str = "\
+####
+
+.#.
+###
+.#.
+
+..#
+..#
+###
 
-    fn add(self, rhs: Self) -> Self {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
+#
+#
+#
+#
+
+##
+##";
+
+/// Shape of a rock, with the lower left corner of the shape at 0,0.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Piece(Vec<Point>);
+
+impl Piece {
+    fn width(&self) -> i64 {
+        self.0.iter().map(|point| point.x() + 1).max().unwrap_or(0)
     }
 }
 
-impl AddAssign<Self> for Point {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
+/// Parses a shape drawn with `#` for rock and `.` for air, top row first.
+impl FromStr for Piece {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let grid = Vec2d::from_str_grid(s, |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(format!("Invalid shape character {:?}", c)),
+        })?;
+        let mut points: Vec<Point> = grid
+            .iter()
+            .filter(|&(_, &rock)| rock)
+            .map(|(p, _)| Point::new(p.x() as i64, (grid.height - 1) as i64 - p.y() as i64))
+            .collect();
+        let left = points
+            .iter()
+            .map(|p| p.x())
+            .min()
+            .ok_or("Shape without rock")?;
+        let bottom = points.iter().map(|p| p.y()).min().unwrap_or(0);
+        points.sort_by_key(|p| (p.y(), p.x()));
+        Ok(Piece(points) + Offset::new(-left, -bottom))
     }
 }
 
+/// Parses shapes separated by empty lines, like [`SHAPES`].
+pub fn parse_shapes(s: &str) -> Result<Vec<Piece>, Error> {
+    util::parse_blocks(s.trim_end(), str::parse)
+}
+
+/// The well the rocks fall into: its width, the shapes falling in turn, and
+/// where a new rock appears.
 #[derive(PartialEq, Eq, Debug, Clone)]
-struct Piece(Vec<Point>);
+pub struct Chamber {
+    shapes: Vec<Piece>,
+    width: i64,
+    spawn: Offset,
+}
 
-impl Add<Point> for Piece {
+impl Chamber {
+    /// New rocks appear `spawn_left` units away from the left wall and
+    /// `spawn_above` units above the highest rock or the floor.
+    pub fn new(
+        shapes: Vec<Piece>,
+        width: i64,
+        spawn_left: i64,
+        spawn_above: i64,
+    ) -> Result<Chamber, Error> {
+        if !(1..=64).contains(&width) {
+            return Err(Error::new("Width must be between 1 and 64"));
+        }
+        if shapes.is_empty() {
+            return Err(Error::new("No shapes given"));
+        }
+        if spawn_left < 0 || spawn_above < 0 {
+            return Err(Error::new("Rocks must appear inside the chamber"));
+        }
+        if let Some(n) = shapes
+            .iter()
+            .position(|shape| spawn_left + shape.width() > width)
+        {
+            return Err(Error::new(format!("Shape {} doesn't fit", n + 1)));
+        }
+        Ok(Chamber {
+            shapes,
+            width,
+            spawn: Offset::new(spawn_left, spawn_above),
+        })
+    }
+
+    /// Height of the tower after `rocks` rocks have fallen. Once the game
+    /// gets into a state it has been in before, the rocks in between repeat
+    /// until the end, so whole repetitions are skipped.
+    pub fn height_after(&self, directions: &[Direction], rocks: u64) -> i64 {
+        let mut game = Game::new(self, directions);
+        let mut seen = HashMap::new();
+        while game.dropped < rocks {
+            if let Some(&(dropped, height)) = seen.get(&game.state()) {
+                let period = game.dropped - dropped;
+                let growth = game.height() - height;
+                let repetitions = (rocks - game.dropped) / period;
+                for _ in 0..(rocks - game.dropped) % period {
+                    game.drop();
+                }
+                return game.height() + repetitions as i64 * growth;
+            }
+            seen.insert(game.state(), (game.dropped, game.height()));
+            game.drop();
+        }
+        game.height()
+    }
+
+    /// Every step of the first `rocks` rocks falling into the chamber.
+    pub fn trace(&self, directions: &[Direction], rocks: u64) -> Vec<Event> {
+        let mut game = Game::new(self, directions);
+        let mut events = Vec::new();
+        for _ in 0..rocks {
+            game.drop_traced(|event| events.push(event));
+        }
+        events
+    }
+}
+
+/// The chamber of the puzzle.
+impl Default for Chamber {
+    fn default() -> Self {
+        let shapes = parse_shapes(SHAPES).expect("puzzle shapes should be valid");
+        Chamber::new(shapes, 7, 2, 3).expect("puzzle chamber should be valid")
+    }
+}
+
+impl Add<Offset> for Piece {
     type Output = Self;
-    fn add(mut self, rhs: Point) -> Self {
+    fn add(mut self, rhs: Offset) -> Self {
         self.0.iter_mut().for_each(|p| *p += rhs);
         self
     }
 }
 
-impl AddAssign<Point> for Piece {
-    fn add_assign(&mut self, rhs: Point) {
+impl AddAssign<Offset> for Piece {
+    fn add_assign(&mut self, rhs: Offset) {
         self.0.iter_mut().for_each(|p| *p += rhs);
     }
 }
 
-struct Game<'a> {
+/// Settled rock, stored as one bitmask per row with bit x set for rock in
+/// column x. Rows that no falling piece can reach anymore are dropped from the
+/// bottom and count as rock from then on, and so are rows too far below the
+/// top for a piece to fall down to in practice.
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Tower {
     width: i64,
-    points: BTreeSet<Point>,
-    shapes: Cycle<Iter<'a, Piece>>,
-    directions: Cycle<Iter<'a, Direction>>,
+    rows: VecDeque<u64>,
+    discarded: i64,
+    prune_at: usize,
+}
+
+impl Tower {
+    /// Number of stored rows after which unreachable ones are looked for.
+    const PRUNE_AT: usize = 128;
+    /// Most rows ever stored, even if a column stays open all the way down.
+    const MAX_ROWS: usize = 1024;
+    /// Deepest a column of the profile goes, so that it fits into a byte.
+    const PROFILE_DEPTH: usize = u8::MAX as usize;
+
+    fn new(width: i64) -> Self {
+        assert!((1..=64).contains(&width), "Width must be between 1 and 64");
+        Tower {
+            width,
+            rows: VecDeque::new(),
+            discarded: 0,
+            prune_at: Tower::PRUNE_AT,
+        }
+    }
+
+    fn height(&self) -> i64 {
+        self.discarded + self.rows.len() as i64
+    }
+
+    fn is_free(&self, point: Point) -> bool {
+        if point.x() < 0 || point.x() >= self.width || point.y() < self.discarded {
+            return false;
+        }
+        let row = (point.y() - self.discarded) as usize;
+        self.rows
+            .get(row)
+            .is_none_or(|&bits| bits & 1 << point.x() == 0)
+    }
+
+    fn insert(&mut self, point: Point) {
+        let row = (point.y() - self.discarded) as usize;
+        if row >= self.rows.len() {
+            self.rows.resize(row + 1, 0);
+        }
+        self.rows[row] |= 1 << point.x();
+        if self.rows.len() >= self.prune_at {
+            self.prune();
+            self.prune_at = (2 * self.rows.len()).clamp(Tower::PRUNE_AT, Tower::MAX_ROWS);
+        }
+    }
+
+    fn fits(&self, piece: &Piece) -> bool {
+        piece.0.iter().all(|&point| self.is_free(point))
+    }
+
+    fn try_move(&self, piece: &Piece, offset: Offset) -> Option<Piece> {
+        let piece = piece.clone() + offset;
+        self.fits(&piece).then_some(piece)
+    }
+
+    fn settle(&mut self, piece: &Piece) {
+        for &point in piece.0.iter() {
+            self.insert(point);
+        }
+    }
+
+    /// Draws the top `rows` rows, with `piece` as `@` and the floor once it
+    /// is in view. Rows dropped from the tower show up as rock.
+    fn to_text(&self, piece: Option<&Piece>, rows: i64) -> String {
+        let top = piece.into_iter().flat_map(|piece| &piece.0);
+        let height = top.map(|p| p.y() + 1).fold(self.height(), i64::max);
+        let rows = rows.min(height);
+        let mut grid = Vec2d::new(self.width as usize, rows as usize, '.');
+        for point in grid.points() {
+            let at = Point::new(point.x() as i64, height - 1 - point.y() as i64);
+            if !self.is_free(at) {
+                grid[point] = '#';
+            } else if piece.is_some_and(|piece| piece.0.contains(&at)) {
+                grid[point] = '@';
+            }
+        }
+        let mut lines: Vec<String> = render::to_text(&grid, |&c| c)
+            .lines()
+            .map(|line| format!("|{}|", line))
+            .collect();
+        if rows == height {
+            lines.push(format!("+{}+", "-".repeat(self.width as usize)));
+        }
+        lines.join("\n")
+    }
+
+    /// Depth of the highest rock in every column below the top of the tower,
+    /// up to [`Tower::PROFILE_DEPTH`]. Columns right of the chamber and the
+    /// dropped rows count as rock.
+    fn profile(&self) -> [u8; 64] {
+        let limit = self.rows.len().min(Tower::PROFILE_DEPTH);
+        let mut profile = [limit as u8; 64];
+        let mut open = u64::MAX >> (64 - self.width);
+        for (depth, &bits) in self.rows.iter().rev().take(limit).enumerate() {
+            let mut found = bits & open;
+            while found != 0 {
+                profile[found.trailing_zeros() as usize] = depth as u8;
+                found &= found - 1;
+            }
+            open &= !bits;
+            if open == 0 {
+                break;
+            }
+        }
+        profile
+    }
+
+    /// Drops the rows below the lowest one a piece could still get into.
+    /// Pieces only move sideways and down, so following the free cells down
+    /// from the open top, spreading sideways in every row, finds all of them.
+    /// Of the rest, only the top half of [`Tower::MAX_ROWS`] is kept.
+    fn prune(&mut self) {
+        let full = u64::MAX >> (64 - self.width);
+        let mut reachable = full;
+        let mut lowest = self.rows.len();
+        for (row, &bits) in self.rows.iter().enumerate().rev() {
+            let free = !bits & full;
+            reachable &= free;
+            loop {
+                let spread = (reachable | reachable << 1 | reachable >> 1) & free;
+                if spread == reachable {
+                    break;
+                }
+                reachable = spread;
+            }
+            if reachable == 0 {
+                break;
+            }
+            lowest = row;
+        }
+        let lowest = lowest.max(self.rows.len().saturating_sub(Tower::MAX_ROWS / 2));
+        self.rows.drain(..lowest);
+        self.discarded += lowest as i64;
+    }
+}
+
+struct Game<'a> {
+    tower: Tower,
+    chamber: &'a Chamber,
+    directions: &'a [Direction],
+    /// Index of the next shape.
+    shape: usize,
+    /// Index of the next jet.
+    jet: usize,
+    dropped: u64,
 }
 
 impl<'a> Game<'a> {
-    fn new(shapes: &'a [Piece], directions: &'a [Direction]) -> Self {
+    fn new(chamber: &'a Chamber, directions: &'a [Direction]) -> Self {
         Self {
-            width: 7,
-            points: BTreeSet::new(),
-            shapes: shapes.iter().cycle(),
-            directions: directions.iter().cycle(),
+            tower: Tower::new(chamber.width),
+            chamber,
+            directions,
+            shape: 0,
+            jet: 0,
+            dropped: 0,
         }
     }
 
     fn height(&self) -> i64 {
-        if let Some(point) = self.points.last() {
-            point.0 + 1
-        } else {
-            0
-        }
+        self.tower.height()
+    }
+
+    /// What decides how the game goes on: the next shape and jet and the
+    /// surface of the tower.
+    fn state(&self) -> (usize, usize, [u8; 64]) {
+        (self.shape, self.jet, self.tower.profile())
     }
 
     fn drop(&mut self) {
-        let mut piece = self
-            .shapes
-            .next()
-            .expect("there should always be a next piece")
-            .clone()
-            + Point(self.height() + 3, 2);
+        self.drop_traced(|_| ());
+    }
+
+    /// Drops the next rock, passing every step it takes to `record`.
+    fn drop_traced<F: FnMut(Event)>(&mut self, mut record: F) {
+        let shape = self.shape;
+        let at = Point::new(0, self.height()) + self.chamber.spawn;
+        let mut piece = self.chamber.shapes[shape].clone() + (at - Point::new(0, 0));
+        record(Event::Spawn { shape, at });
+        self.shape = (self.shape + 1) % self.chamber.shapes.len();
+        self.dropped += 1;
         loop {
-            let h_offset = match *self
-                .directions
-                .next()
-                .expect("there should always be a horizontal direction")
-            {
-                Direction::Left => Point(0, -1),
-                Direction::Right => Point(0, 1),
-            };
-            if let Some(p) = self.try_move(&piece, h_offset) {
+            let direction = self.directions[self.jet];
+            self.jet = (self.jet + 1) % self.directions.len();
+            let moved = self.tower.try_move(&piece, direction.offset());
+            record(Event::Push {
+                direction,
+                blocked: moved.is_none(),
+            });
+            if let Some(p) = moved {
                 piece = p;
             }
-            if let Some(p) = self.try_move(&piece, Point(-1, 0)) {
+            if let Some(p) = self.tower.try_move(&piece, Offset::new(0, -1)) {
+                record(Event::Fall);
                 piece = p;
             } else {
+                record(Event::Settle);
                 break;
             }
         }
-        for &point in piece.0.iter() {
-            self.points.insert(point);
+        self.tower.settle(&piece);
+    }
+
+    /// Draws the top `rows` rows of the tower, with the floor once it is in
+    /// view. Rows dropped from the tower show up as rock.
+    #[allow(dead_code)]
+    fn to_text(&self, rows: i64) -> String {
+        self.tower.to_text(None, rows)
+    }
+}
+
+/// A step of a falling rock.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Event {
+    /// A rock of the given shape appears with the lower left corner of the
+    /// shape at `at`, counting rows from the floor.
+    Spawn { shape: usize, at: Point },
+    /// A jet pushes the rock sideways, unless a wall or rock is in the way.
+    Push { direction: Direction, blocked: bool },
+    /// The rock falls down one row.
+    Fall,
+    /// The rock can't fall any further and comes to rest.
+    Settle,
+}
+
+/// Writes the event the way it is parsed, like `spawn 0 2,3`, `push <`,
+/// `push > blocked`, `fall` or `settle`.
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Spawn { shape, at } => write!(f, "spawn {} {}", shape, at),
+            Event::Push { direction, blocked } => {
+                let jet = match direction {
+                    Direction::Left => '<',
+                    Direction::Right => '>',
+                };
+                write!(f, "push {}{}", jet, if *blocked { " blocked" } else { "" })
+            }
+            Event::Fall => write!(f, "fall"),
+            Event::Settle => write!(f, "settle"),
         }
     }
+}
 
-    fn try_move(&self, piece: &Piece, offset: Point) -> Option<Piece> {
-        let piece: Piece = piece.clone() + offset;
-        if piece.0.iter().all(|point| {
-            point.1 >= 0 && point.1 < self.width && point.0 >= 0 && !self.points.contains(point)
-        }) {
-            Some(piece)
-        } else {
-            None
+impl FromStr for Event {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words[..] {
+            ["spawn", shape, at] => Ok(Event::Spawn {
+                shape: util::parse_value(shape)?,
+                at: at.parse()?,
+            }),
+            ["push", jet] | ["push", jet, "blocked"] => Ok(Event::Push {
+                direction: match jet {
+                    "<" => Direction::Left,
+                    ">" => Direction::Right,
+                    _ => return Err(Error::new(format!("Invalid jet {:?}", jet))),
+                },
+                blocked: words.len() == 3,
+            }),
+            ["fall"] => Ok(Event::Fall),
+            ["settle"] => Ok(Event::Settle),
+            _ => Err(Error::new(format!("Invalid event {:?}", s))),
         }
     }
+}
 
-    #[allow(dead_code)]
-    fn print(&self) {
-        let height = self.height();
-        for line in (0.min(height - 16)..height).rev() {
-            print!("|");
-            for y in 0..self.width {
-                if self.points.contains(&Point(line, y)) {
-                    print!("#");
-                } else {
-                    print!(".");
+/// Parses a trace written by [`write_trace`], one event per line.
+pub fn parse_trace(s: &str) -> Result<Vec<Event>, Error> {
+    util::parse_lines(s, str::parse)
+}
+
+pub fn write_trace<W: Write>(events: &[Event], mut out: W) -> io::Result<()> {
+    for event in events {
+        writeln!(out, "{}", event)?;
+    }
+    Ok(())
+}
+
+pub fn save_trace<P: AsRef<Path>>(events: &[Event], path: P) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_trace(events, &mut out)?;
+    out.flush()
+}
+
+/// Plays a trace back step by step, checking that every event is possible in
+/// the chamber.
+pub struct Replay<'a> {
+    chamber: &'a Chamber,
+    tower: Tower,
+    piece: Option<Piece>,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(chamber: &'a Chamber) -> Self {
+        Replay {
+            chamber,
+            tower: Tower::new(chamber.width),
+            piece: None,
+        }
+    }
+
+    pub fn apply(&mut self, event: Event) -> Result<(), Error> {
+        let piece = match (event, self.piece.take()) {
+            (Event::Spawn { shape, at }, None) => {
+                let shape = self
+                    .chamber
+                    .shapes
+                    .get(shape)
+                    .ok_or_else(|| Error::new(format!("No shape {}", shape)))?;
+                let piece = shape.clone() + (at - Point::new(0, 0));
+                if !self.tower.fits(&piece) {
+                    return Err(Error::new("Rock spawned inside the tower"));
+                }
+                piece
+            }
+            (Event::Spawn { .. }, Some(_)) => {
+                return Err(Error::new("Rock spawned before the last one settled"))
+            }
+            (_, None) => return Err(Error::new("No falling rock")),
+            (Event::Push { direction, blocked }, Some(piece)) => {
+                match self.tower.try_move(&piece, direction.offset()) {
+                    Some(moved) if !blocked => moved,
+                    None if blocked => piece,
+                    _ => return Err(Error::new("Push doesn't match the chamber")),
                 }
             }
-            println!("|");
-        }
-        if height < 16 {
-            println!("+-------+");
-        }
-    }
-}
-
-fn get_shapes() -> Vec<Piece> {
-    vec![
-        // ####
-        Piece(vec![Point(0, 0), Point(0, 1), Point(0, 2), Point(0, 3)]),
-        // .#.
-        // ###
-        // .#.
-        Piece(vec![
-            Point(0, 1),
-            Point(1, 0),
-            Point(1, 1),
-            Point(1, 2),
-            Point(2, 1),
-        ]),
-        // ..#
-        // ..#
-        // ###
-        Piece(vec![
-            Point(0, 0),
-            Point(0, 1),
-            Point(0, 2),
-            Point(1, 2),
-            Point(2, 2),
-        ]),
-        // #
-        // #
-        // #
-        // #
-        Piece(vec![Point(0, 0), Point(1, 0), Point(2, 0), Point(3, 0)]),
-        // ##
-        // ##
-        Piece(vec![Point(0, 0), Point(0, 1), Point(1, 0), Point(1, 1)]),
-    ]
+            (Event::Fall, Some(piece)) => self
+                .tower
+                .try_move(&piece, Offset::new(0, -1))
+                .ok_or("Rock can't fall")?,
+            (Event::Settle, Some(piece)) => {
+                if self.tower.try_move(&piece, Offset::new(0, -1)).is_some() {
+                    return Err(Error::new("Rock settled in the air"));
+                }
+                self.tower.settle(&piece);
+                return Ok(());
+            }
+        };
+        self.piece = Some(piece);
+        Ok(())
+    }
+
+    /// Draws the top `rows` rows like the puzzle does, with the falling rock
+    /// as `@`.
+    pub fn to_text(&self, rows: i64) -> String {
+        self.tower.to_text(self.piece.as_ref(), rows)
+    }
+}
+
+/// Replays `events` in `chamber`, drawing the top `rows` rows after every
+/// event.
+pub fn replay(chamber: &Chamber, events: &[Event], rows: i64) -> Result<Vec<String>, Error> {
+    let mut replay = Replay::new(chamber);
+    events
+        .iter()
+        .enumerate()
+        .map(|(n, &event)| {
+            replay.apply(event).map_err(|error| error.at_line(n + 1))?;
+            Ok(replay.to_text(rows))
+        })
+        .collect()
+}
+
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>",
+    part1: Some("3068"),
+    part2: Some("1514285714288"),
+}];
+
+pub struct Day17;
+
+impl util::Solution for Day17 {
+    type Input<'a> = Vec<Direction>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
 }
 
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day17>(17, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,19 +633,229 @@ mod tests {
         use Direction::*;
         assert_eq!(
             parse(">><>><>"),
-            vec![Right, Right, Left, Right, Right, Left, Right]
+            Ok(vec![Right, Right, Left, Right, Right, Left, Right])
+        );
+        assert_eq!(parse(">><x").unwrap_err().column, Some(4));
+        assert!(parse("\n").is_err());
+    }
+
+    #[test]
+    fn can_draw_tower() {
+        let directions = parse(EXAMPLES[0].input).unwrap();
+        let chamber = Chamber::default();
+        let mut game = Game::new(&chamber, &directions);
+        game.drop();
+        assert_eq!(game.to_text(16), "|..####.|\n+-------+");
+        game.drop();
+        let want = "\
+|...#...|
+|..###..|
+|...#...|
+|..####.|
++-------+";
+        assert_eq!(game.to_text(16), want);
+        assert_eq!(game.to_text(2), "|...#...|\n|..###..|");
+    }
+
+    #[test]
+    fn drops_unreachable_rows() {
+        let mut tower = Tower::new(7);
+        for x in 0..4 {
+            tower.insert(Point::new(x, 0));
+        }
+        for x in 3..7 {
+            tower.insert(Point::new(x, 1));
+        }
+        tower.insert(Point::new(0, 3));
+        tower.prune();
+        assert_eq!((tower.discarded, tower.height()), (1, 4));
+        for x in 0..3 {
+            tower.insert(Point::new(x, 2));
+        }
+        tower.prune();
+        assert_eq!((tower.discarded, tower.height()), (2, 4));
+        assert!(!tower.is_free(Point::new(0, 1)));
+        assert!(tower.is_free(Point::new(3, 2)));
+        assert!(tower.is_free(Point::new(3, 5)));
+        assert!(!tower.is_free(Point::new(7, 5)));
+    }
+
+    #[test]
+    fn keeps_tower_small() {
+        let directions = parse(EXAMPLES[0].input).unwrap();
+        let chamber = Chamber::default();
+        let mut game = Game::new(&chamber, &directions);
+        for _ in 0..100_000 {
+            game.drop();
+        }
+        assert!(game.tower.rows.len() < 2 * Tower::PRUNE_AT);
+        assert!(game.tower.discarded > 100_000);
+    }
+
+    #[test]
+    fn limits_rows_with_open_column() {
+        let directions = [Direction::Right];
+        let chamber = Chamber::default();
+        let mut game = Game::new(&chamber, &directions);
+        for _ in 0..1_000_000 {
+            game.drop();
+            assert!(game.tower.rows.len() <= Tower::MAX_ROWS);
+        }
+        assert!(game.tower.is_free(Point::new(0, game.height() - 1)));
+        assert!(game.tower.discarded > 1_000_000);
+    }
+
+    #[test]
+    fn can_take_profile() {
+        let mut tower = Tower::new(4);
+        assert_eq!(tower.profile()[..5], [0; 5]);
+        tower.insert(Point::new(0, 0));
+        tower.insert(Point::new(2, 2));
+        tower.insert(Point::new(2, 1));
+        assert_eq!(tower.profile()[..5], [2, 3, 0, 3, 3]);
+        for y in 3..300 {
+            tower.insert(Point::new(3, y));
+        }
+        assert_eq!(tower.profile()[..5], [255, 255, 255, 0, 255]);
+    }
+
+    #[test]
+    fn finishes_with_one_jet() {
+        for directions in [[Direction::Right], [Direction::Left]] {
+            let chamber = Chamber::default();
+            let mut game = Game::new(&chamber, &directions);
+            for rocks in 0..2000 {
+                if rocks % 97 == 0 {
+                    assert_eq!(chamber.height_after(&directions, rocks), game.height());
+                }
+                game.drop();
+            }
+            assert!(chamber.height_after(&directions, 1_000_000_000_000) > 0);
+        }
+    }
+
+    #[test]
+    fn skips_repetitions() {
+        let directions = parse(EXAMPLES[0].input).unwrap();
+        let chamber = Chamber::default();
+        let mut game = Game::new(&chamber, &directions);
+        for rocks in 0..10_000 {
+            if rocks % 97 == 0 || rocks < 10 {
+                assert_eq!(height_after(&directions, rocks), game.height());
+            }
+            game.drop();
+        }
+    }
+
+    #[test]
+    fn can_parse_shapes() {
+        let shapes = parse_shapes(SHAPES).unwrap();
+        assert_eq!(shapes.len(), 5);
+        let plus = Piece(
+            [(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)]
+                .map(|(x, y)| Point::new(x, y))
+                .to_vec(),
+        );
+        assert_eq!(shapes[1], plus);
+        assert_eq!(shapes[2].0[4], Point::new(2, 2));
+        assert_eq!("..\n.#\n..".parse(), Ok(Piece(vec![Point::new(0, 0)])));
+        let error = parse_shapes("##\n\n#\n#x").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(4), Some(2)));
+        let error = parse_shapes("##\n\n..").unwrap_err();
+        assert_eq!(error.line, Some(3));
+    }
+
+    #[test]
+    fn can_change_chamber() {
+        let directions = parse(EXAMPLES[0].input).unwrap();
+        let dot = parse_shapes("#").unwrap();
+        let chamber = Chamber::new(dot.clone(), 1, 0, 3).unwrap();
+        assert_eq!(chamber.height_after(&directions, 1_000_000), 1_000_000);
+        let shapes = parse_shapes(SHAPES).unwrap();
+        let chamber = Chamber::new(shapes.clone(), 7, 2, 3).unwrap();
+        assert_eq!(chamber, Chamber::default());
+        assert_eq!(chamber.height_after(&directions, 2022), 3068);
+        let wide = Chamber::new(shapes.clone(), 12, 0, 0).unwrap();
+        let mut game = Game::new(&wide, &directions);
+        game.drop();
+        assert_eq!(game.to_text(1), "|.####.......|\n+------------+");
+        assert!(Chamber::new(shapes.clone(), 7, 4, 3).is_err());
+        assert!(Chamber::new(shapes, 65, 2, 3).is_err());
+        assert!(Chamber::new(Vec::new(), 7, 2, 3).is_err());
+        assert!(Chamber::new(dot, 7, 2, -1).is_err());
+    }
+
+    #[test]
+    fn can_trace_rocks() {
+        let directions = parse(EXAMPLES[0].input).unwrap();
+        let chamber = Chamber::default();
+        let events = chamber.trace(&directions, 1);
+        let want = [
+            "spawn 0 2,3",
+            "push >",
+            "fall",
+            "push > blocked",
+            "fall",
+            "push > blocked",
+            "fall",
+            "push <",
+            "settle",
+        ];
+        let have: Vec<String> = events.iter().map(Event::to_string).collect();
+        assert_eq!(have, want);
+        let events = chamber.trace(&directions, 2022);
+        assert_eq!(events.iter().filter(|&&e| e == Event::Settle).count(), 2022);
+        let mut text = Vec::new();
+        write_trace(&events, &mut text).unwrap();
+        assert_eq!(parse_trace(&String::from_utf8(text).unwrap()), Ok(events));
+        let error = parse_trace("fall\npush ^").unwrap_err();
+        assert_eq!(error.line, Some(2));
+        assert!(parse_trace("spawn 0 2").is_err());
+    }
+
+    #[test]
+    fn can_replay_trace() {
+        let directions = parse(EXAMPLES[0].input).unwrap();
+        let chamber = Chamber::default();
+        let events = chamber.trace(&directions, 2);
+        let frames = replay(&chamber, &events, 8).unwrap();
+        assert_eq!(frames.len(), events.len());
+        let want = "\
+|..@@@@.|
+|.......|
+|.......|
+|.......|
++-------+";
+        assert_eq!(frames[0], want);
+        assert_eq!(frames[1], want.replace("..@@@@.", "...@@@@"));
+        assert_eq!(frames[8], "|..####.|\n+-------+");
+        let mut game = Game::new(&chamber, &directions);
+        game.drop();
+        game.drop();
+        assert_eq!(frames.last().unwrap(), &game.to_text(8));
+        let mut replay = Replay::new(&chamber);
+        replay.apply(events[0]).unwrap();
+        assert_eq!(replay.to_text(1), "|..@@@@.|");
+        let events = parse_trace("spawn 0 2,3\npush > blocked").unwrap();
+        let error = self::replay(&chamber, &events, 8).unwrap_err();
+        assert_eq!(error.line, Some(2));
+        let events = parse_trace("spawn 0 2,0\nsettle\nspawn 0 2,0").unwrap();
+        assert_eq!(
+            self::replay(&chamber, &events, 8).unwrap_err().line,
+            Some(3)
         );
+        assert!(self::replay(&chamber, &[Event::Fall], 8).is_err());
     }
 
     #[test]
     fn solve_part1() {
-        let data = parse(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>");
+        let data = parse(EXAMPLES[0].input).unwrap();
         assert_eq!(part1(&data), 3068);
     }
 
     #[test]
     fn solve_part2() {
-        let data = parse(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>");
+        let data = parse(EXAMPLES[0].input).unwrap();
         assert_eq!(part2(&data), 1_514_285_714_288);
     }
 }
@@ -1,58 +1,13 @@
 use std::collections::HashSet;
-use std::ops::Add;
-use std::str::FromStr;
-
-#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
-pub struct Point(i32, i32, i32);
-
-impl Point {
-    fn neighbours(&self) -> Vec<Point> {
-        let offsets = vec![
-            Point(1, 0, 0),
-            Point(-1, 0, 0),
-            Point(0, 1, 0),
-            Point(0, -1, 0),
-            Point(0, 0, 1),
-            Point(0, 0, -1),
-        ];
-        offsets.iter().map(|offset| *self + *offset).collect()
-    }
-}
+use util::point::{self, Offset};
+use util::search;
+use util::Error;
 
-impl Add<Point> for Point {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
-    }
-}
-
-impl FromStr for Point {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(',');
-        Ok(Point(
-            iter.next()
-                .ok_or("not enough values (got 0)")?
-                .parse()
-                .map_err(|_| "Error parsing first int")?,
-            iter.next()
-                .ok_or("not enough values (got 1)")?
-                .parse()
-                .map_err(|_| "Error parsing second int")?,
-            iter.next()
-                .ok_or("not enough values (got 2)")?
-                .parse()
-                .map_err(|_| "Error parsing third int")?,
-        ))
-    }
-}
+/// Position of a cube of lava.
+pub type Point = point::Point<i32, 3>;
 
-pub fn parse(s: &str) -> Vec<Point> {
-    s.lines()
-        .map(str::parse)
-        .collect::<Result<Vec<Point>, String>>()
-        .expect("Parse error")
+pub fn parse(s: &str) -> Result<Vec<Point>, Error> {
+    util::parse_lines(s, str::parse::<Point>)
 }
 
 pub fn part1(points: &[Point]) -> i32 {
@@ -61,8 +16,7 @@ pub fn part1(points: &[Point]) -> i32 {
         .iter()
         .map(|point| {
             point
-                .neighbours()
-                .iter()
+                .neighbors()
                 .filter(|point| !hash.contains(point))
                 .count() as i32
             //
@@ -71,45 +25,32 @@ pub fn part1(points: &[Point]) -> i32 {
 }
 
 fn find_bounds(points: &HashSet<Point>) -> (Point, Point) {
-    let mut lower = Point(i32::max_value(), i32::max_value(), i32::max_value());
-    let mut upper = Point(i32::min_value(), i32::min_value(), i32::min_value());
+    let mut lower = Point::new(i32::MAX, i32::MAX, i32::MAX);
+    let mut upper = Point::new(i32::MIN, i32::MIN, i32::MIN);
     for point in points.iter() {
-        lower.0 = lower.0.min(point.0);
-        lower.1 = lower.1.min(point.1);
-        lower.2 = lower.2.min(point.2);
-        upper.0 = upper.0.max(point.0);
-        upper.1 = upper.1.max(point.1);
-        upper.2 = upper.2.max(point.2);
+        for (i, &c) in point.0.iter().enumerate() {
+            lower.0[i] = lower.0[i].min(c);
+            upper.0[i] = upper.0[i].max(c);
+        }
     }
-    (lower + Point(-1, -1, -1), upper + Point(1, 1, 1))
+    let margin = Offset([1; 3]);
+    (lower - margin, upper + margin)
 }
 
 fn is_oob(lower: &Point, upper: &Point, point: &Point) -> bool {
-    !(lower.0..=upper.0).contains(&point.0)
-        || !(lower.1..=upper.1).contains(&point.1)
-        || !(lower.1..=upper.2).contains(&point.2)
+    (0..3).any(|i| !(lower.0[i]..=upper.0[i]).contains(&point.0[i]))
 }
 
 fn find_outside_points(points: &HashSet<Point>) -> HashSet<Point> {
-    let mut outside: HashSet<Point> = HashSet::new();
     let (lower_bound, upper_bound) = find_bounds(points);
-    let mut queue: Vec<Point> = vec![lower_bound];
-    while let Some(point) = queue.pop() {
-        if outside.contains(&point) {
-            continue;
-        }
-        outside.insert(point);
-        for neighbor in point.neighbours().iter() {
-            if outside.contains(neighbor)
-                || points.contains(neighbor)
-                || is_oob(&lower_bound, &upper_bound, neighbor)
-            {
-                continue;
-            }
-            queue.push(*neighbor);
-        }
-    }
-    outside
+    search::bfs(lower_bound, |point| {
+        point.neighbors().filter(|neighbor| {
+            !points.contains(neighbor) && !is_oob(&lower_bound, &upper_bound, neighbor)
+        })
+    })
+    .distances
+    .into_keys()
+    .collect()
 }
 
 pub fn part2(points: &[Point]) -> i32 {
@@ -119,8 +60,7 @@ pub fn part2(points: &[Point]) -> i32 {
         .iter()
         .map(|point| {
             point
-                .neighbours()
-                .iter()
+                .neighbors()
                 .filter(|point| !hash.contains(point))
                 .filter(|point| outside.contains(point))
                 .count() as i32
@@ -128,6 +68,50 @@ pub fn part2(points: &[Point]) -> i32 {
         .sum()
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5",
+    part1: Some("64"),
+    part2: Some("58"),
+}];
+
+pub struct Day18;
+
+impl util::Solution for Day18 {
+    type Input<'a> = Vec<Point>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        parse(s)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        Ok(part2(input))
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day18>(18, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,26 +119,19 @@ mod tests {
     #[test]
     fn parse_point() {
         let have = "25,10,13";
-        assert_eq!(have.parse(), Ok(Point(25, 10, 13)));
+        assert_eq!(have.parse(), Ok(Point::new(25, 10, 13)));
+    }
+
+    #[test]
+    fn reports_invalid_lines() {
+        assert_eq!(parse("2,2,2\n1,2").unwrap_err().line, Some(2));
+        assert_eq!(parse("2,2,2\n1,2,2\n1,x,2").unwrap_err().line, Some(3));
     }
 
     #[test]
     fn solve() {
-        let have = "\
-            2,2,2\n\
-            1,2,2\n\
-            3,2,2\n\
-            2,1,2\n\
-            2,3,2\n\
-            2,2,1\n\
-            2,2,3\n\
-            2,2,4\n\
-            2,2,6\n\
-            1,2,5\n\
-            3,2,5\n\
-            2,1,5\n\
-            2,3,5";
-        let data = parse(have);
+        let have = EXAMPLES[0].input;
+        let data = parse(have).unwrap();
         assert_eq!(part1(&data), 64);
         assert_eq!(part2(&data), 58);
     }
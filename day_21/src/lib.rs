@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use symbol::Symbol;
+use util::Error;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum Op {
@@ -12,14 +13,14 @@ pub enum Op {
 }
 
 impl FromStr for Op {
-    type Err = ();
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts: Vec<&str> = s.split_whitespace().collect();
         if parts.len() == 1 {
-            return Ok(Op::Value(parts[0].parse().map_err(|_| ())?));
+            return Ok(Op::Value(util::parse_value(parts[0])?));
         }
         if parts.len() != 3 {
-            return Err(());
+            return Err(Error::new(format!("Invalid operation {:?}", s)));
         }
         let lhs = Symbol::from(parts[0]);
         let rhs = Symbol::from(parts[2]);
@@ -28,7 +29,7 @@ impl FromStr for Op {
             "-" => Ok(Op::Subtract(lhs, rhs)),
             "*" => Ok(Op::Multiply(lhs, rhs)),
             "/" => Ok(Op::Divide(lhs, rhs)),
-            _ => Err(()),
+            op => Err(Error::new(format!("Unknown operator {:?}", op))),
         }
     }
 }
@@ -61,26 +62,40 @@ impl Op {
 #[derive(PartialEq, Eq, Debug)]
 pub struct Operations(HashMap<Symbol, Op>);
 
-fn parse_line(s: &str) -> (Symbol, Op) {
-    let (key, op) = s
-        .split_once(": ")
-        .expect("line should always contain a colon");
-    (
-        key.into(),
-        op.parse().expect("parsing op should never fail"),
-    )
+fn parse_line(s: &str) -> Result<(Symbol, Op), Error> {
+    let (key, op) = s.split_once(": ").ok_or("Line doesn't contain a colon")?;
+    Ok((key.into(), op.parse()?))
 }
 
 impl FromStr for Operations {
-    type Err = ();
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut ops = Operations(s.lines().map(parse_line).collect());
+        let lines = util::parse_lines(s, parse_line)?;
+        let mut keys: HashSet<Symbol> = lines.iter().map(|(key, _)| *key).collect();
+        // Only part 1 needs to know what humn yells.
+        keys.insert("humn".into());
+        for (n, (_, op)) in lines.iter().enumerate() {
+            if let Some((lhs, rhs)) = op.operands() {
+                if let Some(name) = [lhs, rhs].iter().find(|name| !keys.contains(name)) {
+                    return Err(Error::new(format!("Unknown monkey {}", name)).at_line(n + 1));
+                }
+            }
+        }
+        let mut ops = Operations(lines.into_iter().collect());
         ops.reduce();
         Ok(ops)
     }
 }
 
 impl Operations {
+    fn check_known(&self, key: Symbol) -> Result<(), Error> {
+        if self.0.contains_key(&key) {
+            Ok(())
+        } else {
+            Err(Error::new(format!("There is no monkey named {}", key)))
+        }
+    }
+
     fn calc(&self, key: Symbol) -> i64 {
         match self.0[&key] {
             Op::Value(n) => n,
@@ -189,23 +204,78 @@ impl Operations {
     }
 }
 
-pub fn part1(ops: &Operations) -> i64 {
-    ops.calc("root".into())
+/// Fails if root or humn, when some monkey waits for it, is missing.
+pub fn part1(ops: &Operations) -> Result<i64, Error> {
+    ops.check_known("root".into())?;
+    if ops.0.values().any(|op| {
+        op.operands()
+            .is_some_and(|(lhs, rhs)| lhs == "humn" || rhs == "humn")
+    }) {
+        ops.check_known("humn".into())?;
+    }
+    Ok(ops.calc("root".into()))
 }
 
-pub fn part2(ops: Operations) -> i64 {
+/// Fails unless root compares two monkeys, one of them depending on humn.
+pub fn part2(ops: &Operations) -> Result<i64, Error> {
+    ops.check_known("root".into())?;
     let (lhs, rhs) = ops.0[&"root".into()]
         .operands()
-        .expect("root should never be a Value");
-    if let Some(n) = ops.0[&lhs].get_value() {
-        ops.equate(rhs, n)
-    } else if let Some(n) = ops.0[&rhs].get_value() {
-        ops.equate(lhs, n)
-    } else {
-        panic!("Neither lhs nor rhs are a value");
+        .ok_or("root must combine two other monkeys")?;
+    match (ops.get_value(lhs), ops.get_value(rhs)) {
+        (Some(_), Some(_)) => Err(Error::new("root doesn't depend on humn")),
+        (Some(n), None) => Ok(ops.equate(rhs, n)),
+        (None, Some(n)) => Ok(ops.equate(lhs, n)),
+        (None, None) => panic!("Neither lhs nor rhs are a value"),
     }
 }
 
+/// Example from the puzzle description.
+pub const EXAMPLES: &[util::Example] = &[util::Example {
+    input: "\
+root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32",
+    part1: Some("152"),
+    part2: Some("301"),
+}];
+
+pub struct Day21;
+
+impl util::Solution for Day21 {
+    type Input<'a> = Operations;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+        s.parse()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+        part2(input)
+    }
+}
+
+pub const PUZZLE: util::Puzzle =
+    util::Puzzle::new::<Day21>(21, concat!(env!("CARGO_MANIFEST_DIR"), "/input"))
+        .with_examples(EXAMPLES);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -228,45 +298,44 @@ mod tests {
         assert_eq!(have.parse(), Ok(Operations(want)));
     }
 
+    #[test]
+    fn reports_invalid_lines() {
+        let tests = vec![
+            ("root: pppw + lgvd\npppw: 5\nlgvd 2", Some(3)),
+            ("root: pppw + lgvd\npppw: 5\nlgvd: 2 % 3", Some(3)),
+            ("root: pppw + lgvd\npppw: 5\nlgvd: pppw * ptdq", Some(3)),
+        ];
+        for (have, line) in tests {
+            assert_eq!(have.parse::<Operations>().unwrap_err().line, line);
+        }
+    }
+
+    #[test]
+    fn reports_unsolvable_parts() {
+        let tests = vec![
+            ("pppw: 5", None, None),
+            ("root: humn + aaaa\naaaa: 3", None, Some(3)),
+            ("root: aaaa + aaaa\naaaa: 3", Some(6), None),
+            ("humn: 1\nroot: 5", Some(5), None),
+        ];
+        for (have, p1, p2) in tests {
+            let ops = have.parse().unwrap();
+            assert_eq!(part1(&ops).ok(), p1);
+            assert_eq!(part2(&ops).ok(), p2);
+        }
+    }
+
     #[test]
     fn solve_part1() {
-        let have = "root: pppw + sjmn\n\
-        dbpl: 5\n\
-        cczh: sllz + lgvd\n\
-        zczc: 2\n\
-        ptdq: humn - dvpt\n\
-        dvpt: 3\n\
-        lfqf: 4\n\
-        humn: 5\n\
-        ljgn: 2\n\
-        sjmn: drzm * dbpl\n\
-        sllz: 4\n\
-        pppw: cczh / lfqf\n\
-        lgvd: ljgn * ptdq\n\
-        drzm: hmdt - zczc\n\
-        hmdt: 32";
+        let have = EXAMPLES[0].input;
         let data = have.parse().unwrap();
-        assert_eq!(part1(&data), 152);
+        assert_eq!(part1(&data), Ok(152));
     }
 
     #[test]
     fn solve_part2() {
-        let have = "root: pppw + sjmn\n\
-        dbpl: 5\n\
-        cczh: sllz + lgvd\n\
-        zczc: 2\n\
-        ptdq: humn - dvpt\n\
-        dvpt: 3\n\
-        lfqf: 4\n\
-        humn: 5\n\
-        ljgn: 2\n\
-        sjmn: drzm * dbpl\n\
-        sllz: 4\n\
-        pppw: cczh / lfqf\n\
-        lgvd: ljgn * ptdq\n\
-        drzm: hmdt - zczc\n\
-        hmdt: 32";
+        let have = EXAMPLES[0].input;
         let data = have.parse().unwrap();
-        assert_eq!(part2(data), 301);
+        assert_eq!(part2(&data), Ok(301));
     }
 }
@@ -1,9 +1,5 @@
-fn run(file: &str) {
-    let data = file.parse().unwrap();
-    println!("Part 1: {}", day_21::part1(&data));
-    println!("Part 2: {}", day_21::part2(data));
-}
+use std::process::ExitCode;
 
-fn main() {
-    util::execute(run);
+fn main() -> ExitCode {
+    util::execute(day_21::PUZZLE)
 }
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use toml::{Table, Value};
+
+use crate::{parse_value, Error};
+
+/// Known-correct answers for one puzzle input.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Expected {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Expected {
+    pub fn part(&self, part: u32) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            _ => self.part2.as_deref(),
+        }
+    }
+
+    /// Returns whether `answer` is the expected one, ignoring trailing
+    /// whitespace. `None` if there is no expected answer for `part`.
+    pub fn check(&self, part: u32, answer: &str) -> Option<bool> {
+        let expected = self.part(part)?;
+        Some(expected.trim_end() == answer.trim_end())
+    }
+}
+
+/// Manifest of known-correct answers, keyed by day and input file name:
+///
+/// ```toml
+/// [12.input]
+/// part1 = 408
+/// part2 = "399"
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Answers(HashMap<(u32, String), Expected>);
+
+impl Answers {
+    pub fn load(path: &str) -> Result<Answers, String> {
+        let s = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+        s.parse().map_err(|error| format!("{}: {}", path, error))
+    }
+
+    /// Expected answers for an input file of `day`. Only the last component of
+    /// `file_name` is used, so inputs can be found wherever they are run from.
+    pub fn get(&self, day: u32, file_name: &str) -> Option<&Expected> {
+        let name = Path::new(file_name).file_name()?.to_str()?;
+        self.0.get(&(day, name.to_string()))
+    }
+}
+
+fn toml_error(s: &str, error: toml::de::Error) -> Error {
+    let result = Error::new(error.message());
+    match error.span() {
+        Some(span) => {
+            let before = &s[..span.start];
+            let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+            result
+                .at_line(before.matches('\n').count() + 1)
+                .at_column(before[line_start..].chars().count() + 1)
+        }
+        None => result,
+    }
+}
+
+fn parse_answer(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(answer) => Ok(answer.clone()),
+        Value::Integer(answer) => Ok(answer.to_string()),
+        _ => Err(format!(
+            "Answer must be a string or an integer, not {}",
+            value
+        )),
+    }
+}
+
+fn parse_expected(table: &Table) -> Result<Expected, String> {
+    let mut expected = Expected::default();
+    for (key, value) in table {
+        match key.as_str() {
+            "part1" => expected.part1 = Some(parse_answer(value)?),
+            "part2" => expected.part2 = Some(parse_answer(value)?),
+            _ => return Err(format!("Unknown key {:?}", key)),
+        }
+    }
+    Ok(expected)
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: Table = s.parse().map_err(|error| toml_error(s, error))?;
+        let mut answers = HashMap::new();
+        for (day, files) in table {
+            let files = files
+                .as_table()
+                .ok_or_else(|| format!("Day {} must be a table of input files", day))?;
+            let day: u32 = parse_value(&day)?;
+            for (file_name, parts) in files {
+                let parts = parts
+                    .as_table()
+                    .ok_or_else(|| format!("Answers for {} must be a table", file_name))?;
+                let expected = parse_expected(parts)
+                    .map_err(|message| format!("Day {}, {}: {}", day, file_name, message))?;
+                answers.insert((day, file_name.clone()), expected);
+            }
+        }
+        Ok(Answers(answers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_answers() {
+        let have: Answers = "[12.input]\npart1 = 408\npart2 = \"399\"\n\
+                             [10.\"example.txt\"]\npart2 = \"\"\"\n#.\n.#\n\"\"\""
+            .parse()
+            .unwrap();
+        let day_12 = have.get(12, "day_12/input").unwrap();
+        assert_eq!(day_12.check(1, "408"), Some(true));
+        assert_eq!(day_12.check(2, "398"), Some(false));
+        let day_10 = have.get(10, "example.txt").unwrap();
+        assert_eq!(day_10.check(1, "13140"), None);
+        assert_eq!(day_10.check(2, "#.\n.#"), Some(true));
+        assert_eq!(have.get(12, "example.txt"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_answers() {
+        let error = "[12.input]\npart1 = 408\npart3 = 1".parse::<Answers>();
+        assert!(error.is_err());
+        let error = "[12.input]\npart1 = ".parse::<Answers>().unwrap_err();
+        assert_eq!(error.line, Some(2));
+        assert!("[x.input]\npart1 = 1".parse::<Answers>().is_err());
+        assert!("[12]\ninput = 1".parse::<Answers>().is_err());
+    }
+}
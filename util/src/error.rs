@@ -0,0 +1,150 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Error produced for invalid puzzle input, while parsing it or solving a
+/// part that can't handle it.
+///
+/// Line and column are 1-based and refer to the position in the input the
+/// error was found at, as far as it is known.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Error {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new<S: Into<String>>(message: S) -> Error {
+        Error {
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Error {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn at_column(mut self, column: usize) -> Error {
+        self.column = Some(column);
+        self
+    }
+
+    /// Moves an error found in a block of lines to its position in the
+    /// surrounding input, where the block starts after `lines` lines.
+    pub fn offset_line(mut self, lines: usize) -> Error {
+        self.line = Some(self.line.unwrap_or(1) + lines);
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                write!(f, "line {}, column {}: {}", line, column, self.message)
+            }
+            (Some(line), None) => write!(f, "line {}: {}", line, self.message),
+            (None, Some(column)) => write!(f, "column {}: {}", column, self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::new(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::new(message)
+    }
+}
+
+/// Parses `s` with its `FromStr` implementation, keeping the offending
+/// string in the error message.
+pub fn parse_value<T>(s: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.parse()
+        .map_err(|error| Error::new(format!("Error parsing {:?}: {}", s, error)))
+}
+
+/// Parses every line of `s` with `f`, attaching the line number to errors.
+pub fn parse_lines<'a, T, E, F>(s: &'a str, mut f: F) -> Result<Vec<T>, Error>
+where
+    E: Into<Error>,
+    F: FnMut(&'a str) -> Result<T, E>,
+{
+    s.lines()
+        .enumerate()
+        .map(|(n, line)| f(line).map_err(|error| error.into().at_line(n + 1)))
+        .collect()
+}
+
+/// Parses every block of lines of `s` separated by an empty line with `f`.
+///
+/// Line numbers of errors are taken as relative to the start of the block and
+/// converted to lines of `s`.
+pub fn parse_blocks<'a, T, E, F>(s: &'a str, mut f: F) -> Result<Vec<T>, Error>
+where
+    E: Into<Error>,
+    F: FnMut(&'a str) -> Result<T, E>,
+{
+    let mut offset = 0;
+    s.split("\n\n")
+        .map(|block| {
+            let result = f(block).map_err(|error| error.into().offset_line(offset));
+            offset += block.lines().count() + 1;
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_display_position() {
+        let tests = vec![
+            (Error::new("oops"), "oops"),
+            (Error::new("oops").at_line(3), "line 3: oops"),
+            (
+                Error::new("oops").at_line(3).at_column(7),
+                "line 3, column 7: oops",
+            ),
+        ];
+        for (have, want) in tests {
+            assert_eq!(have.to_string(), want);
+        }
+    }
+
+    #[test]
+    fn can_parse_lines() {
+        assert_eq!(
+            parse_lines("1\n2\n3", parse_value::<i32>),
+            Ok(vec![1, 2, 3])
+        );
+        let error = parse_lines("1\nx\n3", parse_value::<i32>).unwrap_err();
+        assert_eq!(error.line, Some(2));
+    }
+
+    #[test]
+    fn can_parse_blocks() {
+        let have = "1\n2\n\n3\n4";
+        let sum =
+            |block: &str| parse_lines(block, parse_value::<i32>).map(|v| v.iter().sum::<i32>());
+        assert_eq!(parse_blocks(have, sum), Ok(vec![3, 7]));
+        let error = parse_blocks("1\n2\n\n3\nx", sum).unwrap_err();
+        assert_eq!(error.line, Some(5));
+    }
+}
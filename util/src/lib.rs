@@ -1,25 +1,74 @@
-use std::env;
-use std::fs;
-use std::io;
-
+mod answers;
+mod error;
+mod options;
+pub mod point;
+mod pool;
+pub mod render;
+mod runner;
+pub mod search;
+mod solution;
 pub mod vec2d;
 
-pub fn execute<F>(f: F)
-where
-    F: Fn(&str),
-{
-    for file_name in env::args().skip(1) {
-        if let Err(error) = execute_file(&file_name, &f) {
-            println!("Error reading file {}: {}", file_name, error);
+pub use answers::{Answers, Expected};
+pub use error::{parse_blocks, parse_lines, parse_value, Error};
+pub use options::{Format, Options};
+pub use runner::{execute, execute_examples, execute_files, STDIN};
+pub use solution::{solve, Report, Solution, Timings};
+
+/// Example input from a puzzle description together with the answers given
+/// there, if they apply to the solution as a whole.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    pub fn expected(&self) -> Expected {
+        Expected {
+            part1: self.part1.map(String::from),
+            part2: self.part2.map(String::from),
         }
     }
 }
 
-fn execute_file<F>(file_name: &str, f: &F) -> io::Result<()>
-where
-    F: Fn(&str),
-{
-    let file: String = fs::read_to_string(file_name)?;
-    f(&file);
-    Ok(())
+/// Registration of a day's solution, shared by the day binaries and the
+/// `aoc` runner.
+#[derive(Clone, Copy)]
+pub struct Puzzle {
+    pub day: u32,
+    /// Path of the puzzle input checked into the day's crate.
+    pub input: &'static str,
+    pub examples: &'static [Example],
+    /// Solves the given part of an input, or both parts for `None`.
+    pub solve: fn(&str, Option<u32>) -> Result<Report, Error>,
+    /// Solves the examples like `solve`, which is what it does unless the
+    /// examples ask different questions than the puzzle input does.
+    pub solve_example: fn(&str, Option<u32>) -> Result<Report, Error>,
+}
+
+impl Puzzle {
+    pub const fn new<S: Solution>(day: u32, input: &'static str) -> Puzzle {
+        Puzzle {
+            day,
+            input,
+            examples: &[],
+            solve: solve::<S>,
+            solve_example: solve::<S>,
+        }
+    }
+
+    pub const fn with_examples(self, examples: &'static [Example]) -> Puzzle {
+        Puzzle { examples, ..self }
+    }
+
+    /// Solves the examples with `S`, for days whose examples use other
+    /// parameters than the puzzle, like a smaller area to search.
+    pub const fn with_example_solution<S: Solution>(self) -> Puzzle {
+        Puzzle {
+            solve_example: solve::<S>,
+            ..self
+        }
+    }
 }
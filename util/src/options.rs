@@ -0,0 +1,144 @@
+use crate::Answers;
+
+/// How results are printed.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    /// One JSON object per input file and line.
+    Json,
+}
+
+/// Command line options understood by the day binaries and the `aoc` runner.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Options {
+    /// Print how long parsing and each part took.
+    pub time: bool,
+    /// Solve every input this many times and report timing statistics.
+    pub bench: Option<usize>,
+    /// Known-correct answers to check the results against.
+    pub answers: Option<Answers>,
+    pub format: Format,
+    /// Solve only this part instead of both.
+    pub part: Option<u32>,
+    /// Solve the examples from the puzzle description instead of files.
+    pub example: bool,
+    /// Solve this many inputs at the same time.
+    pub jobs: Option<usize>,
+}
+
+impl Options {
+    pub const USAGE: &'static str =
+        "[--time] [--bench N] [--answers FILE] [--format text|json] [--part 1|2] [--jobs N] [--example | FILE...]";
+
+    /// Splits command line arguments into options and file names.
+    pub fn parse<I>(args: I) -> Result<(Options, Vec<String>), String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = Options::default();
+        let mut file_names = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--time" => options.time = true,
+                "--example" => options.example = true,
+                "--bench" => {
+                    let runs = args
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or("--bench needs a positive number of runs")?;
+                    options.bench = Some(runs);
+                }
+                "--jobs" => {
+                    let jobs = args
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or("--jobs needs a positive number of threads")?;
+                    options.jobs = Some(jobs);
+                }
+                "--answers" => {
+                    let path = args.next().ok_or("--answers needs a file name")?;
+                    options.answers = Some(Answers::load(&path)?);
+                }
+                "--format" => {
+                    options.format = match args.next().as_deref() {
+                        Some("text") => Format::Text,
+                        Some("json") => Format::Json,
+                        _ => return Err(String::from("--format needs text or json")),
+                    };
+                }
+                "--part" => {
+                    let part = args
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|n| [1, 2].contains(n))
+                        .ok_or("--part needs 1 or 2")?;
+                    options.part = Some(part);
+                }
+                option if option.starts_with("--") => {
+                    return Err(format!("Unknown option {}", option));
+                }
+                _ => file_names.push(arg),
+            }
+        }
+        if options.example && !file_names.is_empty() {
+            return Err(String::from("--example doesn't take any files"));
+        }
+        Ok((options, file_names))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn can_parse_options() {
+        let (options, files) = Options::parse(args(
+            "--time a.txt - --bench 5 --format json --part 2 --jobs 4 b.txt",
+        ))
+        .unwrap();
+        assert_eq!(
+            options,
+            Options {
+                time: true,
+                bench: Some(5),
+                answers: None,
+                format: Format::Json,
+                part: Some(2),
+                example: false,
+                jobs: Some(4),
+            }
+        );
+        assert_eq!(files, args("a.txt - b.txt"));
+        let (options, files) = Options::parse(args("--example --part 1")).unwrap();
+        assert!(options.example);
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        for have in [
+            "--bench",
+            "--bench 0",
+            "--bench x",
+            "--verbose",
+            "--answers",
+            "--answers /nonexistent/answers.toml",
+            "--format",
+            "--format xml",
+            "--part 3",
+            "--example a.txt",
+            "--jobs 0",
+        ] {
+            assert!(Options::parse(args(have)).is_err());
+        }
+    }
+}
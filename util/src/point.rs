@@ -0,0 +1,334 @@
+//! Points and offsets with any number of integer coordinates.
+
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Integer types usable as coordinates.
+pub trait Coordinate:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + FromStr
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+}
+
+macro_rules! coordinate {
+    ($($t:ty),*) => {
+        $(impl Coordinate for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+        })*
+    };
+}
+
+coordinate!(i8, i16, i32, i64, i128, isize);
+
+/// Position with `N` coordinates of type `T`. Crates name the kind they work
+/// with, like [`crate::vec2d::Point`], which also lets `new` tell the
+/// dimension from the type.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
+pub struct Point<T, const N: usize>(pub [T; N]);
+
+/// Difference between two points.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
+pub struct Offset<T, const N: usize>(pub [T; N]);
+
+impl<T: Copy> Point<T, 2> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point([x, y])
+    }
+
+    pub fn x(self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(self) -> T {
+        self.0[1]
+    }
+}
+
+impl<T: Copy> Point<T, 3> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point([x, y, z])
+    }
+
+    pub fn x(self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(self) -> T {
+        self.0[2]
+    }
+}
+
+impl<T: Copy> Offset<T, 2> {
+    pub const fn new(x: T, y: T) -> Self {
+        Offset([x, y])
+    }
+
+    pub fn x(self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(self) -> T {
+        self.0[1]
+    }
+}
+
+impl<T: Copy> Offset<T, 3> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Offset([x, y, z])
+    }
+
+    pub fn x(self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(self) -> T {
+        self.0[2]
+    }
+}
+
+impl<T: Coordinate, const N: usize> Offset<T, N> {
+    pub const ZERO: Self = Offset([T::ZERO; N]);
+
+    /// Steps to all points of the surrounding cube, sorted by the last
+    /// coordinate first, which for a grid is row by row.
+    fn around() -> impl Iterator<Item = Self> {
+        let unit = [T::ZERO - T::ONE, T::ZERO, T::ONE];
+        (0..3usize.pow(N as u32))
+            .map(move |n| Offset(std::array::from_fn(|i| unit[n / 3usize.pow(i as u32) % 3])))
+            .filter(|&offset| offset != Offset::ZERO)
+    }
+
+    /// Steps along a single axis, to the neighbors sharing a side.
+    pub fn orthogonal() -> impl Iterator<Item = Self> {
+        Offset::around().filter(|offset| offset.manhattan() == T::ONE)
+    }
+
+    /// Steps to all neighbors, including the diagonal ones.
+    pub fn adjacent() -> impl Iterator<Item = Self> {
+        Offset::around()
+    }
+
+    /// Number of steps along the axes it takes to cover the offset.
+    pub fn manhattan(self) -> T {
+        self.0.iter().fold(T::ZERO, |sum, &c| sum + c.abs())
+    }
+
+    /// Number of steps it takes to cover the offset if diagonal steps are
+    /// allowed.
+    pub fn chebyshev(self) -> T {
+        self.0.iter().fold(T::ZERO, |max, &c| max.max(c.abs()))
+    }
+}
+
+impl<T: Coordinate, const N: usize> Point<T, N> {
+    /// Points sharing a side with this one.
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        Offset::orthogonal().map(move |offset| self + offset)
+    }
+
+    /// All surrounding points, including the diagonal neighbors.
+    pub fn adjacent(self) -> impl Iterator<Item = Self> {
+        Offset::adjacent().map(move |offset| self + offset)
+    }
+
+    pub fn manhattan(self, other: Self) -> T {
+        (self - other).manhattan()
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        (self - other).chebyshev()
+    }
+}
+
+fn zip<T: Copy, const N: usize>(a: [T; N], b: [T; N], f: impl Fn(T, T) -> T) -> [T; N] {
+    std::array::from_fn(|i| f(a[i], b[i]))
+}
+
+impl<T: Coordinate, const N: usize> Add<Offset<T, N>> for Point<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Offset<T, N>) -> Self::Output {
+        Point(zip(self.0, rhs.0, T::add))
+    }
+}
+
+impl<T: Coordinate, const N: usize> AddAssign<Offset<T, N>> for Point<T, N> {
+    fn add_assign(&mut self, rhs: Offset<T, N>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coordinate, const N: usize> Sub<Offset<T, N>> for Point<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Offset<T, N>) -> Self::Output {
+        Point(zip(self.0, rhs.0, T::sub))
+    }
+}
+
+impl<T: Coordinate, const N: usize> SubAssign<Offset<T, N>> for Point<T, N> {
+    fn sub_assign(&mut self, rhs: Offset<T, N>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Coordinate, const N: usize> Sub<Point<T, N>> for Point<T, N> {
+    type Output = Offset<T, N>;
+
+    fn sub(self, rhs: Point<T, N>) -> Self::Output {
+        Offset(zip(self.0, rhs.0, T::sub))
+    }
+}
+
+impl<T: Coordinate, const N: usize> Add<Offset<T, N>> for Offset<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Offset<T, N>) -> Self::Output {
+        Offset(zip(self.0, rhs.0, T::add))
+    }
+}
+
+impl<T: Coordinate, const N: usize> Sub<Offset<T, N>> for Offset<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Offset<T, N>) -> Self::Output {
+        Offset(zip(self.0, rhs.0, T::sub))
+    }
+}
+
+impl<T: Coordinate, const N: usize> Mul<T> for Offset<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Offset(self.0.map(|c| c * rhs))
+    }
+}
+
+impl<T: Coordinate, const N: usize> Neg for Offset<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Offset::ZERO - self
+    }
+}
+
+/// Writes the coordinates separated by commas, the way they are parsed.
+impl<T: Display, const N: usize> Display for Point<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, c) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses coordinates separated by commas, like `498,4`.
+impl<T: Coordinate, const N: usize> FromStr for Point<T, N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values: Vec<&str> = s.split(',').map(str::trim).collect();
+        if values.len() != N {
+            return Err(format!("Expected {} coordinates, got {}", N, values.len()));
+        }
+        let mut point = Point([T::ZERO; N]);
+        for (c, value) in point.0.iter_mut().zip(values) {
+            *c = value
+                .parse()
+                .map_err(|_| format!("Error parsing int {}", value))?;
+        }
+        Ok(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Point2 = Point<i32, 2>;
+    type Offset2 = Offset<i32, 2>;
+    type Point3 = Point<i64, 3>;
+    type Offset3 = Offset<i64, 3>;
+
+    #[test]
+    fn can_do_arithmetic() {
+        let point = Point2::new(5, 5);
+        assert_eq!(point + Offset2::new(1, -1), Point2::new(6, 4));
+        assert_eq!(point - Offset2::new(1, -1), Point2::new(4, 6));
+        assert_eq!(Point2::new(6, 4) - point, Offset2::new(1, -1));
+        assert_eq!(Offset2::new(1, -2) * 3, Offset2::new(3, -6));
+        assert_eq!(
+            -Offset2::new(1, -2) + Offset2::new(1, 1),
+            Offset2::new(0, 3)
+        );
+        let mut point = Point3::new(1, 2, 3);
+        point += Offset3::new(1, 1, 1);
+        point -= Offset3::new(0, 0, 2);
+        assert_eq!(point, Point3::new(2, 3, 2));
+        assert_eq!((point.x(), point.y(), point.z()), (2, 3, 2));
+    }
+
+    #[test]
+    fn can_measure_distances() {
+        let a = Point2::new(1, 5);
+        let b = Point2::new(4, 1);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        let c = Point3::new(0, 0, 0);
+        assert_eq!(c.manhattan(Point3::new(-1, 2, -3)), 6);
+        assert_eq!(c.chebyshev(Point3::new(-1, 2, -3)), 3);
+    }
+
+    #[test]
+    fn can_find_neighbors() {
+        let point = Point2::new(0, 0);
+        let have: Vec<_> = point.neighbors().collect();
+        let want = [(0, -1), (-1, 0), (1, 0), (0, 1)].map(|(x, y)| Point2::new(x, y));
+        assert_eq!(have, want);
+        let have: Vec<_> = point.adjacent().collect();
+        assert_eq!(have.len(), 8);
+        assert_eq!((have[0], have[7]), (Point2::new(-1, -1), Point2::new(1, 1)));
+        let point = Point3::new(1, 1, 1);
+        assert_eq!(point.neighbors().count(), 6);
+        assert!(point.neighbors().all(|n| n.manhattan(point) == 1));
+        assert_eq!(point.adjacent().count(), 26);
+    }
+
+    #[test]
+    fn can_parse_and_print() {
+        assert_eq!("498,4".parse(), Ok(Point2::new(498, 4)));
+        assert_eq!("2, -2, 5".parse(), Ok(Point3::new(2, -2, 5)));
+        assert!("1,2".parse::<Point3>().is_err());
+        assert!("1,x".parse::<Point2>().is_err());
+        assert_eq!(Point3::new(1, -2, 3).to_string(), "1,-2,3");
+    }
+}
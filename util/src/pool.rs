@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Applies `f` to every item on up to `jobs` threads and hands the results to
+/// `consume` in the order of the items, each as soon as it and all results
+/// before it are done. Without `jobs` everything runs on the calling thread.
+pub(crate) fn map_ordered<T, R, F, C>(items: &[T], jobs: Option<usize>, f: F, mut consume: C)
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    C: FnMut(R),
+{
+    let jobs = jobs.unwrap_or(1).min(items.len());
+    if jobs <= 1 {
+        items.iter().map(f).for_each(consume);
+        return;
+    }
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (next, f) = (&next, &f);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                if sender.send((index, f(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&expected) {
+                consume(result);
+                expected += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn keeps_order_of_items() {
+        let items: Vec<u64> = (0..20).collect();
+        for jobs in [None, Some(1), Some(4), Some(50)] {
+            let mut have = Vec::new();
+            map_ordered(
+                &items,
+                jobs,
+                |&n| {
+                    thread::sleep(Duration::from_millis((20 - n) % 7));
+                    n * n
+                },
+                |n| have.push(n),
+            );
+            let want: Vec<u64> = items.iter().map(|n| n * n).collect();
+            assert_eq!(have, want);
+        }
+    }
+}
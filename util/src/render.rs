@@ -0,0 +1,185 @@
+//! Turning grids into text or images to look at what a simulation does.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::vec2d::{Point, Vec2d};
+
+/// Renders `grid` as text with one line per row, using `f` to pick the
+/// character for every cell.
+pub fn to_text<T, F: FnMut(&T) -> char>(grid: &Vec2d<T>, mut f: F) -> String {
+    if grid.width == 0 {
+        return String::new();
+    }
+    grid.rows()
+        .map(|row| row.iter().map(&mut f).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Grid covering the bounding box of `points`, with the fields in the set
+/// being true. The top left corner of the grid is the smallest coordinate of
+/// the points.
+pub fn points_to_grid(points: &HashSet<Point>) -> Vec2d<bool> {
+    let (Some(min_x), Some(max_x)) = (
+        points.iter().map(|p| p.x()).min(),
+        points.iter().map(|p| p.x()).max(),
+    ) else {
+        return Vec2d::new(0, 0, false);
+    };
+    let min_y = points.iter().map(|p| p.y()).min().unwrap_or_default();
+    let max_y = points.iter().map(|p| p.y()).max().unwrap_or_default();
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+    let mut grid = Vec2d::new(width, height, false);
+    for point in points {
+        grid[Point::new(point.x() - min_x, point.y() - min_y)] = true;
+    }
+    grid
+}
+
+/// Renders the bounding box of `points` as text, using `f` to pick the
+/// character for every cell, given whether it is one of the points.
+pub fn points_to_text<F: FnMut(&bool) -> char>(points: &HashSet<Point>, f: F) -> String {
+    to_text(&points_to_grid(points), f)
+}
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+/// RGB image, stored row by row.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Image(pub Vec2d<Rgb>);
+
+impl Image {
+    /// Renders `grid` with one pixel per cell, using `f` to pick its color.
+    pub fn from_grid<T, F: FnMut(&T) -> Rgb>(grid: &Vec2d<T>, f: F) -> Image {
+        Image(grid.map(f))
+    }
+
+    /// Renders `points` white on black.
+    pub fn from_points(points: &HashSet<Point>) -> Image {
+        Image::from_grid(
+            &points_to_grid(points),
+            |&set| if set { WHITE } else { BLACK },
+        )
+    }
+
+    /// Blows every pixel up to a square of `factor` pixels, to make small
+    /// grids visible.
+    pub fn scaled(&self, factor: usize) -> Image {
+        let pixels = &self.0;
+        let mut scaled = Vec2d::new(pixels.width * factor, pixels.height * factor, BLACK);
+        for point in scaled.points() {
+            let factor = factor as i32;
+            scaled[point] = pixels[Point::new(point.x() / factor, point.y() / factor)];
+        }
+        Image(scaled)
+    }
+
+    /// Writes the image as binary PPM.
+    pub fn write_ppm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.0.width, self.0.height)?;
+        out.write_all(&self.0.data.concat())
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_ppm(&mut out)?;
+        out.flush()
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let out = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(out, self.0.width as u32, self.0.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.0.data.concat()))
+            .map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("util_render_{}", name))
+    }
+
+    #[test]
+    fn can_render_text() {
+        let grid = Vec2d {
+            width: 3,
+            height: 2,
+            data: vec![1, 0, 0, 0, 2, 1],
+        };
+        let have = to_text(&grid, |&n| char::from_digit(n, 10).unwrap());
+        assert_eq!(have, "100\n021");
+        let points = HashSet::from([Point::new(-1, 5), Point::new(1, 6), Point::new(1, 7)]);
+        let pick = |&set: &bool| if set { '#' } else { '.' };
+        assert_eq!(points_to_text(&points, pick), "#..\n..#\n..#");
+        assert_eq!(
+            points_to_text(&points, |&set| if set { 'x' } else { ' ' }),
+            "x  \n  x\n  x"
+        );
+        assert_eq!(points_to_text(&HashSet::new(), pick), "");
+    }
+
+    #[test]
+    fn can_write_ppm() {
+        let points = HashSet::from([Point::new(0, 0), Point::new(1, 1)]);
+        let mut have = Vec::new();
+        Image::from_points(&points).write_ppm(&mut have).unwrap();
+        let mut want = b"P6\n2 2\n255\n".to_vec();
+        want.extend([WHITE, BLACK, BLACK, WHITE].concat());
+        assert_eq!(have, want);
+    }
+
+    #[test]
+    fn can_scale_images() {
+        let image = Image::from_points(&HashSet::from([Point::new(0, 0), Point::new(1, 1)]));
+        let scaled = image.scaled(2);
+        assert_eq!((scaled.0.width, scaled.0.height), (4, 4));
+        assert_eq!(scaled.0[Point::new(1, 1)], WHITE);
+        assert_eq!(scaled.0[Point::new(2, 1)], BLACK);
+        assert_eq!(scaled.0[Point::new(3, 3)], WHITE);
+    }
+
+    #[test]
+    fn can_save_ppm() {
+        let image = Image::from_points(&HashSet::from([Point::new(0, 0), Point::new(2, 1)]));
+        let path = temp_path("image.ppm");
+        image.save_ppm(&path).unwrap();
+        let mut want = Vec::new();
+        image.write_ppm(&mut want).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), want);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn can_save_png() {
+        let points = HashSet::from([Point::new(0, 0), Point::new(2, 1)]);
+        let image = Image::from_points(&points).scaled(2);
+        let path = temp_path("image.png");
+        image.save_png(&path).unwrap();
+        let mut reader = png::Decoder::new(File::open(&path).unwrap())
+            .read_info()
+            .unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (6, 4));
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        assert_eq!(pixels[..info.buffer_size()], image.0.data.concat());
+    }
+}
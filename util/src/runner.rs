@@ -0,0 +1,655 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::{self, Read};
+use std::panic;
+use std::process::ExitCode;
+use std::sync::Once;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::options::{Format, Options};
+use crate::pool;
+use crate::solution::format_answer;
+use crate::{Error, Expected, Puzzle, Report, Timings};
+
+/// Reason a single input file couldn't be solved.
+#[derive(Debug)]
+enum Failure {
+    Read(io::Error),
+    /// Input rejected while parsing or by one of the parts.
+    Invalid(Error),
+    Panic(String),
+    /// Parts whose answers differ from the expected ones.
+    Mismatch(Vec<u32>),
+}
+
+/// Result of comparing the answer of a part with the expected one.
+#[derive(Debug)]
+struct Check {
+    part: u32,
+    expected: String,
+    correct: bool,
+}
+
+/// Everything found out about a single input file.
+#[derive(Debug)]
+struct Outcome {
+    report: Report,
+    checks: Vec<Check>,
+    /// Timings of every run with `--bench`, starting with the one reported.
+    bench: Vec<Timings>,
+    /// Whether answers were given but none for this file.
+    unchecked: bool,
+}
+
+/// Everything printed for a single file. It's put together before printing
+/// anything, so results of files solved at the same time don't interleave.
+struct Output {
+    stdout: Option<String>,
+    stderr: Option<String>,
+    success: bool,
+}
+
+impl Output {
+    /// Prints the output and returns whether the file was solved correctly.
+    fn print(self) -> bool {
+        if let Some(stdout) = self.stdout {
+            println!("{}", stdout);
+        }
+        if let Some(stderr) = self.stderr {
+            eprintln!("{}", stderr);
+        }
+        self.success
+    }
+}
+
+impl Outcome {
+    fn wrong_parts(&self) -> Vec<u32> {
+        self.checks
+            .iter()
+            .filter(|check| !check.correct)
+            .map(|check| check.part)
+            .collect()
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Read(error) => write!(f, "error reading file: {}", error),
+            Failure::Invalid(error) => write!(f, "invalid input: {}", error),
+            Failure::Panic(message) => write!(f, "panicked: {}", message),
+            Failure::Mismatch(parts) => {
+                let parts: Vec<String> = parts.iter().map(u32::to_string).collect();
+                write!(f, "wrong answer for part {}", parts.join(" and "))
+            }
+        }
+    }
+}
+
+thread_local! {
+    /// Whether the thread is solving a puzzle right now.
+    static SOLVING: Cell<bool> = const { Cell::new(false) };
+    /// Where the last panic while solving happened.
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Keeps panics of solutions from being printed right away, which would mix
+/// them into the results of other files. They are reported with the result
+/// of their file instead. Other panics are printed as usual.
+fn hold_back_solution_panics() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if SOLVING.with(Cell::get) {
+                let location = info.location().map(ToString::to_string);
+                PANIC_LOCATION.with(|cell| *cell.borrow_mut() = location);
+            } else {
+                default(info);
+            }
+        }));
+    });
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown cause")
+    }
+}
+
+/// File name standing for standard input.
+pub const STDIN: &str = "-";
+
+/// Solves the puzzle for every file given on the command line, or for
+/// standard input if there are none.
+///
+/// The exit code is a failure if any of the files couldn't be solved.
+pub fn execute(puzzle: Puzzle) -> ExitCode {
+    let mut args = env::args();
+    let program = args.next().unwrap_or_default();
+    let (options, mut file_names) = match Options::parse(args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}\nUsage: {} {}", message, program, Options::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if file_names.is_empty() {
+        file_names.push(String::from(STDIN));
+    }
+    let success = if options.example {
+        execute_examples(puzzle, &options)
+    } else {
+        execute_files(file_names, puzzle, &options)
+    };
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Solves the puzzle for every file, reporting failures and carrying on with
+/// the next file. Returns whether all files were solved correctly.
+///
+/// With `--jobs` files are solved concurrently, but their results are still
+/// printed in the order of `file_names`.
+pub fn execute_files<I>(file_names: I, puzzle: Puzzle, options: &Options) -> bool
+where
+    I: IntoIterator<Item = String>,
+{
+    let file_names: Vec<String> = file_names.into_iter().collect();
+    hold_back_solution_panics();
+    let mut success = true;
+    pool::map_ordered(
+        &file_names,
+        options.jobs,
+        |file_name| {
+            let result = execute_file(file_name, puzzle, options);
+            let label = if file_name == STDIN {
+                "<stdin>"
+            } else {
+                file_name
+            };
+            render_result(file_name, label, puzzle, options, &result)
+        },
+        |output| success &= output.print(),
+    );
+    success
+}
+
+/// Solves the examples from the puzzle description, checking the answers
+/// given there. Returns whether all examples were solved correctly.
+pub fn execute_examples(puzzle: Puzzle, options: &Options) -> bool {
+    if puzzle.examples.is_empty() {
+        eprintln!("Day {} has no examples", puzzle.day);
+    }
+    let examples: Vec<_> = puzzle.examples.iter().enumerate().collect();
+    let puzzle = Puzzle {
+        solve: puzzle.solve_example,
+        ..puzzle
+    };
+    hold_back_solution_panics();
+    let mut success = true;
+    pool::map_ordered(
+        &examples,
+        options.jobs,
+        |(n, example)| {
+            let name = format!("example {}", n + 1);
+            let label = format!("Example {}", n + 1);
+            let result = execute_input(example.input, Some(&example.expected()), puzzle, options);
+            render_result(&name, &label, puzzle, options, &result)
+        },
+        |output| success &= output.print(),
+    );
+    success
+}
+
+fn read_input(file_name: &str) -> io::Result<String> {
+    if file_name == STDIN {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(file_name)
+    }
+}
+
+fn solve(file: &str, puzzle: Puzzle, part: Option<u32>) -> Result<Report, Failure> {
+    SOLVING.with(|solving| solving.set(true));
+    let result = panic::catch_unwind(|| (puzzle.solve)(file, part));
+    SOLVING.with(|solving| solving.set(false));
+    result
+        .map_err(|payload| {
+            let message = panic_message(payload);
+            match PANIC_LOCATION.with(RefCell::take) {
+                Some(location) => Failure::Panic(format!("{} at {}", message, location)),
+                None => Failure::Panic(message),
+            }
+        })?
+        .map_err(Failure::Invalid)
+}
+
+fn execute_file(file_name: &str, puzzle: Puzzle, options: &Options) -> Result<Outcome, Failure> {
+    let file: String = read_input(file_name).map_err(Failure::Read)?;
+    let expected = options
+        .answers
+        .as_ref()
+        .and_then(|answers| answers.get(puzzle.day, file_name));
+    let mut outcome = execute_input(&file, expected, puzzle, options)?;
+    outcome.unchecked = options.answers.is_some() && expected.is_none();
+    Ok(outcome)
+}
+
+fn execute_input(
+    file: &str,
+    expected: Option<&Expected>,
+    puzzle: Puzzle,
+    options: &Options,
+) -> Result<Outcome, Failure> {
+    let report = solve(file, puzzle, options.part)?;
+    let mut checks = Vec::new();
+    if let Some(expected) = expected {
+        for (part, answer) in report.answers() {
+            if let Some(correct) = expected.check(part, answer) {
+                let expected = expected.part(part).unwrap_or_default().to_string();
+                checks.push(Check {
+                    part,
+                    expected,
+                    correct,
+                });
+            }
+        }
+    }
+    let mut bench = Vec::new();
+    if let Some(runs) = options.bench {
+        bench.push(report.timings);
+        for _ in 1..runs {
+            bench.push(solve(file, puzzle, options.part)?.timings);
+        }
+    }
+    Ok(Outcome {
+        report,
+        checks,
+        bench,
+        unchecked: false,
+    })
+}
+
+/// Puts together the output for a single file in the format asked for. Text
+/// output starts with `label` naming the input, JSON output with the file
+/// name.
+fn render_result(
+    file_name: &str,
+    label: &str,
+    puzzle: Puzzle,
+    options: &Options,
+    result: &Result<Outcome, Failure>,
+) -> Output {
+    let failure = match result {
+        Ok(outcome) => {
+            let wrong = outcome.wrong_parts();
+            (!wrong.is_empty()).then(|| Failure::Mismatch(wrong).to_string())
+        }
+        Err(failure) => Some(failure.to_string()),
+    };
+    match options.format {
+        Format::Text => Output {
+            stdout: Some(match result {
+                Ok(outcome) => format!("{}\n{}", label, format_text(outcome, options)),
+                Err(_) => label.to_string(),
+            }),
+            stderr: match (&failure, result) {
+                (Some(failure), _) => Some(format!("{}: {}", label, failure)),
+                (None, Ok(outcome)) if outcome.unchecked => {
+                    Some(format!("{}: unchecked, no answers given", label))
+                }
+                _ => None,
+            },
+            success: failure.is_none(),
+        },
+        Format::Json => Output {
+            stdout: Some(format_json(puzzle.day, file_name, result)),
+            stderr: None,
+            success: failure.is_none(),
+        },
+    }
+}
+
+fn format_text(outcome: &Outcome, options: &Options) -> String {
+    let report = &outcome.report;
+    let mut lines: Vec<String> = report
+        .answers()
+        .map(|(part, answer)| format_answer(part, answer))
+        .collect();
+    for check in &outcome.checks {
+        if check.correct {
+            lines.push(format!("Part {}: PASS", check.part));
+        } else {
+            lines.push(format_failure(check.part, &check.expected));
+        }
+    }
+    if options.time {
+        lines.push(format_timings(&report.timings));
+    }
+    if !outcome.bench.is_empty() {
+        lines.push(format_bench(&outcome.bench));
+    }
+    lines.join("\n")
+}
+
+fn format_failure(part: u32, expected: &str) -> String {
+    let expected = expected.trim_end();
+    if expected.contains('\n') {
+        format!("Part {}: FAIL, expected\n{}", part, expected)
+    } else {
+        format!("Part {}: FAIL, expected {}", part, expected)
+    }
+}
+
+fn format_timings(timings: &Timings) -> String {
+    let mut result = format!("Time: parse {:.3?}", timings.parse);
+    for (part, time) in [(1, timings.part1), (2, timings.part2)] {
+        if let Some(time) = time {
+            result += &format!(", part {} {:.3?}", part, time);
+        }
+    }
+    result
+}
+
+/// Returns minimum, median and maximum of `durations`.
+fn statistics(mut durations: Vec<Duration>) -> (Duration, Duration, Duration) {
+    durations.sort();
+    (
+        durations[0],
+        durations[durations.len() / 2],
+        durations[durations.len() - 1],
+    )
+}
+
+/// Durations of every step taken while solving a puzzle, with the step's
+/// JSON key and label.
+fn steps(timings: &[Timings]) -> Vec<(&'static str, &'static str, Vec<Duration>)> {
+    let steps: Vec<(_, _, Vec<Duration>)> = vec![
+        ("parse", "parse ", timings.iter().map(|t| t.parse).collect()),
+        (
+            "part1",
+            "part 1",
+            timings.iter().filter_map(|t| t.part1).collect(),
+        ),
+        (
+            "part2",
+            "part 2",
+            timings.iter().filter_map(|t| t.part2).collect(),
+        ),
+    ];
+    steps
+        .into_iter()
+        .filter(|(_, _, durations)| !durations.is_empty())
+        .collect()
+}
+
+fn format_bench(timings: &[Timings]) -> String {
+    let mut result = format!("Bench over {} runs:", timings.len());
+    for (_, name, durations) in steps(timings) {
+        let (min, median, max) = statistics(durations);
+        result += &format!(
+            "\n  {}  min {:.3?}  median {:.3?}  max {:.3?}",
+            name, min, median, max
+        );
+    }
+    result
+}
+
+/// Formats the result for a single file as one line of JSON. Durations are
+/// given in seconds.
+fn format_json(day: u32, file_name: &str, result: &Result<Outcome, Failure>) -> String {
+    let mut value = json!({ "day": day, "file": file_name });
+    match result {
+        Ok(outcome) => {
+            let report = &outcome.report;
+            value["timings"] = json!({ "parse": report.timings.parse.as_secs_f64() });
+            for (part, answer) in report.answers() {
+                value[format!("part{}", part)] = json!(answer);
+            }
+            for (part, time) in [(1, report.timings.part1), (2, report.timings.part2)] {
+                if let Some(time) = time {
+                    value["timings"][format!("part{}", part)] = json!(time.as_secs_f64());
+                }
+            }
+            for check in &outcome.checks {
+                value["checks"][format!("part{}", check.part)] = json!(check.correct);
+            }
+            if outcome.unchecked {
+                value["unchecked"] = json!(true);
+            }
+            if !outcome.bench.is_empty() {
+                value["bench"] = json!({ "runs": outcome.bench.len() });
+                for (name, _, durations) in steps(&outcome.bench) {
+                    let (min, median, max) = statistics(durations);
+                    value["bench"][name] = json!({
+                        "min": min.as_secs_f64(),
+                        "median": median.as_secs_f64(),
+                        "max": max.as_secs_f64(),
+                    });
+                }
+            }
+            let wrong = outcome.wrong_parts();
+            if !wrong.is_empty() {
+                value["error"] = json!(Failure::Mismatch(wrong).to_string());
+            }
+        }
+        Err(failure) => value["error"] = json!(failure.to_string()),
+    }
+    Value::to_string(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Example, Solution};
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input<'a> = Vec<i32>;
+        type Answer1 = i32;
+        type Answer2 = i32;
+
+        fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+            crate::parse_lines(s, crate::parse_value)
+        }
+
+        fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+            Ok(input[3])
+        }
+    }
+
+    /// Sums up one less than [`Sum`], like a day asking about a smaller
+    /// example.
+    struct SumMinusOne;
+
+    impl Solution for SumMinusOne {
+        type Input<'a> = Vec<i32>;
+        type Answer1 = i32;
+        type Answer2 = i32;
+
+        fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+            Sum::parse(s)
+        }
+
+        fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+            Ok(Sum::part1(input)? - 1)
+        }
+
+        fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+            Sum::part2(input)
+        }
+    }
+
+    fn write_input(name: &str, content: &str) -> String {
+        let path = env::temp_dir().join(format!("util_runner_{}", name));
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn reports_failing_files() {
+        let puzzle = Puzzle::new::<Sum>(0, "");
+        let options = Options::default();
+        let good = write_input("good", "1\n2\n3\n4");
+        let unparsable = write_input("unparsable", "1\nx");
+        let panicking = write_input("panicking", "1\n2");
+        let missing = String::from("/nonexistent/input");
+
+        assert!(execute_file(&good, puzzle, &options).is_ok());
+        assert!(matches!(
+            execute_file(&unparsable, puzzle, &options),
+            Err(Failure::Invalid(_))
+        ));
+        assert!(matches!(
+            execute_file(&panicking, puzzle, &options),
+            Err(Failure::Panic(_))
+        ));
+        assert!(matches!(
+            execute_file(&missing, puzzle, &options),
+            Err(Failure::Read(_))
+        ));
+        assert!(execute_files([good.clone()], puzzle, &options));
+        assert!(!execute_files(
+            [panicking.clone(), good.clone()],
+            puzzle,
+            &options
+        ));
+        let options = Options {
+            jobs: Some(2),
+            ..Options::default()
+        };
+        assert!(execute_files(
+            [good.clone(), good.clone()],
+            puzzle,
+            &options
+        ));
+        assert!(!execute_files([good, panicking], puzzle, &options));
+    }
+
+    #[test]
+    fn reports_wrong_answers() {
+        let puzzle = Puzzle::new::<Sum>(0, "");
+        let file = write_input("checked", "1\n2\n3\n4");
+        let answers = "[0.util_runner_checked]\npart1 = 10\npart2 = 5";
+        let options = Options {
+            answers: Some(answers.parse().unwrap()),
+            ..Options::default()
+        };
+        let outcome = execute_file(&file, puzzle, &options).unwrap();
+        assert_eq!(outcome.wrong_parts(), [2]);
+        assert!(!outcome.unchecked);
+        assert!(!execute_files([file], puzzle, &options));
+
+        let file = write_input("unlisted", "1\n2\n3\n4");
+        let outcome = execute_file(&file, puzzle, &options);
+        assert!(outcome.as_ref().unwrap().unchecked);
+        let output = render_result(&file, &file, puzzle, &options, &outcome);
+        assert!(output.success);
+        assert_eq!(
+            output.stderr.unwrap(),
+            format!("{}: unchecked, no answers given", file)
+        );
+        let json: Value = serde_json::from_str(&format_json(0, &file, &outcome)).unwrap();
+        assert_eq!(json["unchecked"], true);
+    }
+
+    #[test]
+    fn can_format_json() {
+        let puzzle = Puzzle::new::<Sum>(0, "");
+        let file = write_input("json", "1\n2\n3\n4");
+        let result = execute_file(&file, puzzle, &Options::default());
+        let have: Value = serde_json::from_str(&format_json(0, &file, &result)).unwrap();
+        assert_eq!(have["day"], 0);
+        assert_eq!(have["file"], file.as_str());
+        assert_eq!(have["part1"], "10");
+        assert_eq!(have["part2"], "4");
+        assert!(have["timings"]["part2"].is_f64());
+        assert!(have.get("error").is_none());
+
+        let result = execute_file("/nonexistent/input", puzzle, &Options::default());
+        let have: Value =
+            serde_json::from_str(&format_json(0, "/nonexistent/input", &result)).unwrap();
+        assert!(have["error"].is_string());
+        assert!(have.get("part1").is_none());
+    }
+
+    #[test]
+    fn names_inputs_in_text() {
+        let puzzle = Puzzle::new::<Sum>(0, "");
+        let options = Options::default();
+        let file = write_input("text", "1\n2\n3\n4");
+        let result = execute_file(&file, puzzle, &options);
+        let output = render_result(&file, &file, puzzle, &options, &result);
+        assert!(output.stdout.unwrap().starts_with(&format!("{}\n", file)));
+        assert!(output.stderr.is_none());
+
+        hold_back_solution_panics();
+        let file = write_input("text_panicking", "1\n2");
+        let result = execute_file(&file, puzzle, &options);
+        let output = render_result(&file, &file, puzzle, &options, &result);
+        assert_eq!(output.stdout.unwrap(), file);
+        let stderr = output.stderr.unwrap();
+        assert!(stderr.starts_with(&format!("{}: ", file)));
+        assert!(stderr.contains("runner.rs"));
+    }
+
+    #[test]
+    fn checks_examples() {
+        const EXAMPLES: &[Example] = &[
+            Example {
+                input: "1\n2\n3\n4",
+                part1: Some("10"),
+                part2: None,
+            },
+            Example {
+                input: "1\n1\n1\n1",
+                part1: Some("4"),
+                part2: Some("1"),
+            },
+        ];
+        let puzzle = Puzzle::new::<Sum>(0, "").with_examples(EXAMPLES);
+        assert!(execute_examples(puzzle, &Options::default()));
+        const WRONG: &[Example] = &[Example {
+            input: "1\n2\n3\n4",
+            part1: Some("9"),
+            part2: None,
+        }];
+        let puzzle = Puzzle::new::<Sum>(0, "").with_examples(WRONG);
+        assert!(!execute_examples(puzzle, &Options::default()));
+        let puzzle = puzzle.with_example_solution::<SumMinusOne>();
+        assert!(execute_examples(puzzle, &Options::default()));
+    }
+
+    #[test]
+    fn can_compute_statistics() {
+        let durations = [5, 1, 4, 2, 3].map(Duration::from_millis).to_vec();
+        assert_eq!(
+            statistics(durations),
+            (
+                Duration::from_millis(1),
+                Duration::from_millis(3),
+                Duration::from_millis(5)
+            )
+        );
+    }
+}
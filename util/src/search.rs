@@ -0,0 +1,230 @@
+//! Shortest paths in graphs that are given by a function returning the
+//! neighbors of a node.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Distances of all nodes reached by a search, and the way back to its start.
+#[derive(Debug, Clone)]
+pub struct Paths<N, C> {
+    pub distances: HashMap<N, C>,
+    previous: HashMap<N, N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Paths<N, C> {
+    fn new(start: N, zero: C) -> Self {
+        Paths {
+            distances: HashMap::from([(start, zero)]),
+            previous: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// Nodes on a shortest path from the start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.previous.get(path.last()?) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Entry of the priority queue, with the lowest cost coming first.
+struct Entry<N, C>(C, N);
+
+impl<N, C: Ord> PartialEq for Entry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<N, C: Ord> Eq for Entry<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Entry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Entry<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0).reverse()
+    }
+}
+
+/// Breadth-first search from `start`, where every step costs 1.
+pub fn bfs<N, F, I>(start: N, mut neighbors: F) -> Paths<N, usize>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new(start.clone(), 0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = paths.distances[&node] + 1;
+        for next in neighbors(&node) {
+            if paths.distances.contains_key(&next) {
+                continue;
+            }
+            paths.distances.insert(next.clone(), distance);
+            paths.previous.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+    paths
+}
+
+/// Dijkstra's algorithm from `start`, with `neighbors` returning the nodes
+/// next to a node together with the cost of moving there.
+pub fn dijkstra<N, C, F, I>(start: N, neighbors: F) -> Paths<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    dijkstra_from([start], neighbors)
+}
+
+/// Dijkstra's algorithm from several starts at once, giving the distance of
+/// every node to the nearest start. Paths begin at that start.
+pub fn dijkstra_from<N, C, F, I, S>(starts: S, mut neighbors: F) -> Paths<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    S: IntoIterator<Item = N>,
+{
+    let mut paths = Paths {
+        distances: HashMap::new(),
+        previous: HashMap::new(),
+    };
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        paths.distances.insert(start.clone(), C::default());
+        queue.push(Entry(C::default(), start));
+    }
+    while let Some(Entry(cost, node)) = queue.pop() {
+        if paths.distances[&node] < cost {
+            continue;
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if paths.distances.get(&next).is_some_and(|&n| n <= next_cost) {
+                continue;
+            }
+            paths.distances.insert(next.clone(), next_cost);
+            paths.previous.insert(next.clone(), node.clone());
+            queue.push(Entry(next_cost, next));
+        }
+    }
+    paths
+}
+
+/// A* search from `start` to the first node for which `is_goal` holds,
+/// returning the path there and its cost. `heuristic` must never overestimate
+/// the remaining cost to a goal, or the path found may not be the shortest.
+pub fn astar<N, C, F, I, H, G>(
+    start: N,
+    mut neighbors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut paths = Paths::new(start.clone(), C::default());
+    let mut queue = BinaryHeap::from([Entry(heuristic(&start), start)]);
+    while let Some(Entry(_, node)) = queue.pop() {
+        let cost = paths.distances[&node];
+        if is_goal(&node) {
+            return Some((paths.path_to(&node)?, cost));
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if paths.distances.get(&next).is_some_and(|&n| n <= next_cost) {
+                continue;
+            }
+            paths.distances.insert(next.clone(), next_cost);
+            paths.previous.insert(next.clone(), node.clone());
+            queue.push(Entry(next_cost + heuristic(&next), next));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Line of nodes 0 to 9 with a shortcut from 2 to 7 that is cheap but
+    /// takes more steps than walking directly would.
+    fn line(node: &u32) -> Vec<(u32, u32)> {
+        let mut neighbors = Vec::new();
+        if *node > 0 {
+            neighbors.push((node - 1, 3));
+        }
+        if *node < 9 {
+            neighbors.push((node + 1, 3));
+        }
+        if *node == 2 {
+            neighbors.extend([(5, 1), (7, 1)]);
+        }
+        neighbors
+    }
+
+    #[test]
+    fn bfs_counts_steps() {
+        let paths = bfs(0, |&n| line(&n).into_iter().map(|(n, _)| n));
+        assert_eq!(paths.distance(&9), Some(5));
+        assert_eq!(paths.path_to(&8), Some(vec![0, 1, 2, 7, 8]));
+        assert_eq!(paths.path_to(&0), Some(vec![0]));
+        let paths = bfs(3, |&n| (n < 5).then_some(n + 1));
+        assert_eq!(paths.distance(&2), None);
+        assert_eq!(paths.path_to(&6), None);
+        assert_eq!(paths.distances.len(), 3);
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_paths() {
+        let paths = dijkstra(0, line);
+        assert_eq!(paths.distance(&4), Some(10));
+        assert_eq!(paths.path_to(&4), Some(vec![0, 1, 2, 5, 4]));
+        assert_eq!(paths.distance(&9), Some(13));
+        assert_eq!(paths.distances.len(), 10);
+    }
+
+    #[test]
+    fn dijkstra_starts_from_nearest_start() {
+        let paths = dijkstra_from([0, 9], line);
+        assert_eq!(paths.distance(&4), Some(10));
+        assert_eq!(paths.distance(&6), Some(9));
+        assert_eq!(paths.path_to(&6), Some(vec![9, 8, 7, 6]));
+        assert_eq!(paths.path_to(&9), Some(vec![9]));
+        assert_eq!(dijkstra_from([], line).distances.len(), 0);
+    }
+
+    #[test]
+    fn astar_finds_cheapest_path_to_goal() {
+        let have = astar(0, line, |&n| 9 - n.min(9), |&n| n == 9);
+        assert_eq!(have, Some((vec![0, 1, 2, 7, 8, 9], 13)));
+        assert_eq!(astar(0, line, |_| 0, |&n| n == 10), None);
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use crate::Error;
+
+/// Uniform interface of a day's solution.
+///
+/// `parse` turns the puzzle input into `Input`, which both parts then work
+/// on. `Input` may borrow from the puzzle input. Work only one of the parts
+/// needs belongs into that part, so it is skipped when solving the other one
+/// alone. The same goes for checking the input: a part returns an error for
+/// input only it can't handle.
+pub trait Solution {
+    type Input<'a>;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, Error>;
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error>;
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error>;
+}
+
+/// Time taken by each step of solving a puzzle.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+}
+
+/// Answers for one puzzle input. Parts that weren't asked for are `None`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Report {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub timings: Timings,
+}
+
+impl Report {
+    /// Answers of the parts that were solved, with their number.
+    pub fn answers(&self) -> impl Iterator<Item = (u32, &str)> {
+        [(1, &self.part1), (2, &self.part2)]
+            .into_iter()
+            .filter_map(|(part, answer)| Some((part, answer.as_deref()?)))
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Solves `file`, timing every step. With `part` given only that part is
+/// solved, otherwise both are.
+pub fn solve<S: Solution>(file: &str, part: Option<u32>) -> Result<Report, Error> {
+    let (input, parse) = timed(|| S::parse(file));
+    let input = input?;
+    let (part1, part1_time) = match part {
+        Some(2) => (None, None),
+        _ => {
+            let (answer, time) = timed(|| S::part1(&input).map(|a| a.to_string()));
+            (Some(answer?), Some(time))
+        }
+    };
+    let (part2, part2_time) = match part {
+        Some(1) => (None, None),
+        _ => {
+            let (answer, time) = timed(|| S::part2(&input).map(|a| a.to_string()));
+            (Some(answer?), Some(time))
+        }
+    };
+    Ok(Report {
+        part1,
+        part2,
+        timings: Timings {
+            parse,
+            part1: part1_time,
+            part2: part2_time,
+        },
+    })
+}
+
+/// Answers spanning multiple lines start on the line after the label.
+pub(crate) fn format_answer<T: Display>(part: u32, answer: T) -> String {
+    let answer = answer.to_string();
+    if answer.contains('\n') {
+        format!("Part {}:\n{}", part, answer)
+    } else {
+        format!("Part {}: {}", part, answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_format_answers() {
+        let tests = vec![
+            (1, "42", "Part 1: 42"),
+            (2, "CMZ", "Part 2: CMZ"),
+            (2, "#..\n.#.", "Part 2:\n#..\n.#."),
+        ];
+        for (part, answer, want) in tests {
+            assert_eq!(format_answer(part, answer), want);
+        }
+    }
+
+    struct Count;
+
+    impl Solution for Count {
+        type Input<'a> = &'a str;
+        type Answer1 = usize;
+        type Answer2 = usize;
+
+        fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+            Ok(s)
+        }
+
+        fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+            Ok(input.len())
+        }
+
+        fn part2(_: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+            panic!("part 2 must not be solved")
+        }
+    }
+
+    #[test]
+    fn solves_selected_part() {
+        let report = solve::<Count>("abc", Some(1)).unwrap();
+        assert_eq!(report.part1.as_deref(), Some("3"));
+        assert_eq!(report.part2, None);
+        assert_eq!(report.timings.part2, None);
+        assert_eq!(report.answers().collect::<Vec<_>>(), [(1, "3")]);
+    }
+
+    struct NoEmpty;
+
+    impl Solution for NoEmpty {
+        type Input<'a> = &'a str;
+        type Answer1 = usize;
+        type Answer2 = char;
+
+        fn parse(s: &str) -> Result<Self::Input<'_>, Error> {
+            Ok(s)
+        }
+
+        fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1, Error> {
+            Ok(input.len())
+        }
+
+        fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2, Error> {
+            input
+                .chars()
+                .next()
+                .ok_or_else(|| Error::new("Empty input"))
+        }
+    }
+
+    #[test]
+    fn reports_errors_of_parts() {
+        assert_eq!(
+            solve::<NoEmpty>("", Some(1)).unwrap().part1.as_deref(),
+            Some("0")
+        );
+        assert_eq!(
+            solve::<NoEmpty>("", None).unwrap_err().message,
+            "Empty input"
+        );
+    }
+}
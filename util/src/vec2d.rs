@@ -1,50 +1,40 @@
-use std::ops::{Add, AddAssign, Index, IndexMut, Sub, SubAssign};
-use std::str::FromStr;
+use std::fmt::Display;
+use std::iter::StepBy;
+use std::ops::{Index, IndexMut};
+use std::slice;
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
-pub struct Point(pub i32, pub i32);
+use crate::point;
+use crate::Error;
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
-pub struct Offset(pub i32, pub i32);
+/// Position in a grid. The first coordinate is the column, the second one
+/// the row.
+pub type Point = point::Point<i32, 2>;
 
-impl Add<Offset> for Point {
-    type Output = Point;
+pub type Offset = point::Offset<i32, 2>;
 
-    fn add(self, rhs: Offset) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-impl AddAssign<Offset> for Point {
-    fn add_assign(&mut self, rhs: Offset) {
-        *self = *self + rhs;
-    }
-}
-
-impl Sub<Offset> for Point {
-    type Output = Point;
-    fn sub(self, rhs: Offset) -> Self::Output {
-        Point(self.0 - rhs.0, self.1 - rhs.1)
-    }
-}
+impl Offset {
+    /// Steps to the horizontal and vertical neighbors: up, left, right, down.
+    pub const ORTHOGONAL: [Offset; 4] = [
+        Offset::new(0, -1),
+        Offset::new(-1, 0),
+        Offset::new(1, 0),
+        Offset::new(0, 1),
+    ];
 
-impl SubAssign<Offset> for Point {
-    fn sub_assign(&mut self, rhs: Offset) {
-        *self = *self - rhs;
-    }
-}
-
-impl FromStr for Point {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.split_once(',').ok_or("Parse error: No , found")?;
-        Ok(Point(
-            x.parse().map_err(|_| format!("Error parsing int {}", x))?,
-            y.parse().map_err(|_| format!("Error parsing int {}", y))?,
-        ))
-    }
+    /// Steps to all eight surrounding fields, row by row.
+    pub const ADJACENT: [Offset; 8] = [
+        Offset::new(-1, -1),
+        Offset::new(0, -1),
+        Offset::new(1, -1),
+        Offset::new(-1, 0),
+        Offset::new(1, 0),
+        Offset::new(-1, 1),
+        Offset::new(0, 1),
+        Offset::new(1, 1),
+    ];
 }
 
+/// Grid of values stored row by row.
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Vec2d<T> {
     pub width: usize,
@@ -62,15 +52,155 @@ impl<T: Clone> Vec2d<T> {
     }
 }
 
-impl<T: Copy> Vec2d<T> {
-    pub fn get(&self, index: Point) -> Option<T> {
-        if index.0 < 0 || index.1 < 0 {
-            return None;
+impl<T> Vec2d<T> {
+    /// Parses a grid of characters, one row per line, converting every
+    /// character with `f`.
+    ///
+    /// Rows of differing length and characters `f` rejects are reported with
+    /// their line and column.
+    pub fn from_str_grid<E, F>(s: &str, mut f: F) -> Result<Vec2d<T>, Error>
+    where
+        E: Into<Error>,
+        F: FnMut(char) -> Result<T, E>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut data = Vec::new();
+        for (y, line) in s.lines().enumerate() {
+            let mut columns = 0;
+            for (x, c) in line.chars().enumerate() {
+                data.push(f(c).map_err(|error| error.into().at_line(y + 1).at_column(x + 1))?);
+                columns += 1;
+            }
+            let expected = *width.get_or_insert(columns);
+            if columns != expected {
+                return Err(Error::new(format!(
+                    "Row has {} columns instead of {}",
+                    columns, expected
+                ))
+                .at_line(y + 1)
+                .at_column(columns.min(expected) + 1));
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Vec2d {
+                width,
+                height,
+                data,
+            }),
+            _ => Err(Error::new("Empty grid")),
+        }
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Vec2d<U> {
+        Vec2d {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(f).collect(),
         }
-        if (index.0 as usize) < self.width && (index.1 as usize) < self.height {
-            Some(self[index])
-        } else {
-            None
+    }
+
+    fn position(&self, index: Point) -> Option<usize> {
+        let x = usize::try_from(index.x())
+            .ok()
+            .filter(|&x| x < self.width)?;
+        let y = usize::try_from(index.y())
+            .ok()
+            .filter(|&y| y < self.height)?;
+        Some(x + y * self.width)
+    }
+
+    pub fn contains(&self, index: Point) -> bool {
+        self.position(index).is_some()
+    }
+
+    pub fn get(&self, index: Point) -> Option<&T> {
+        self.position(index).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, index: Point) -> Option<&mut T> {
+        self.position(index).map(|i| &mut self.data[i])
+    }
+
+    /// Stores `value` at `index`. Returns false if `index` lies outside of the
+    /// grid, leaving it unchanged.
+    pub fn set(&mut self, index: Point, value: T) -> bool {
+        match self.get_mut(index) {
+            Some(field) => {
+                *field = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Values of row `y` from left to right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is not a row of the grid.
+    pub fn row(&self, y: usize) -> slice::Iter<'_, T> {
+        assert!(y < self.height, "Row {} out of bounds", y);
+        self.data[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    /// Values of column `x` from top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is not a column of the grid.
+    pub fn col(&self, x: usize) -> StepBy<slice::Iter<'_, T>> {
+        assert!(x < self.width, "Column {} out of bounds", x);
+        self.data[x..].iter().step_by(self.width)
+    }
+
+    pub fn rows(&self) -> slice::Chunks<'_, T> {
+        self.data.chunks(self.width)
+    }
+
+    /// All points of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// All points of the grid with their values, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.data.iter())
+    }
+
+    /// Horizontal and vertical neighbors of `index` inside the grid.
+    pub fn neighbors(&self, index: Point) -> impl Iterator<Item = Point> + '_ {
+        Offset::ORTHOGONAL
+            .into_iter()
+            .map(move |offset| index + offset)
+            .filter(|&point| self.contains(point))
+    }
+
+    /// Neighbors of `index` inside the grid, including the diagonal ones.
+    pub fn adjacent(&self, index: Point) -> impl Iterator<Item = Point> + '_ {
+        Offset::ADJACENT
+            .into_iter()
+            .map(move |offset| index + offset)
+            .filter(|&point| self.contains(point))
+    }
+}
+
+impl<T: PartialEq + Display> Vec2d<T> {
+    /// Finds the only point holding `value`, like the start marker of a map.
+    /// It's an error if there is none or more than one, the latter reported
+    /// at the second occurrence.
+    pub fn find_unique(&self, value: &T) -> Result<Point, Error> {
+        let mut found = self.iter().filter(|(_, v)| *v == value).map(|(p, _)| p);
+        let point = found
+            .next()
+            .ok_or_else(|| Error::new(format!("Marker {} not found", value)))?;
+        match found.next() {
+            Some(duplicate) => Err(Error::new(format!("Duplicate marker {}", value))
+                .at_line(duplicate.y() as usize + 1)
+                .at_column(duplicate.x() as usize + 1)),
+            None => Ok(point),
         }
     }
 }
@@ -78,18 +208,18 @@ impl<T: Copy> Vec2d<T> {
 impl<T> Index<Point> for Vec2d<T> {
     type Output = T;
     fn index(&self, index: Point) -> &Self::Output {
-        let x = index.0 as usize;
-        let y = index.1 as usize;
-        let i = x + y * self.width;
+        let i = self
+            .position(index)
+            .expect("point should be inside the grid");
         &self.data[i]
     }
 }
 
 impl<T> IndexMut<Point> for Vec2d<T> {
     fn index_mut(&mut self, index: Point) -> &mut Self::Output {
-        let x = index.0 as usize;
-        let y = index.1 as usize;
-        let i = x + y * self.width;
+        let i = self
+            .position(index)
+            .expect("point should be inside the grid");
         &mut self.data[i]
     }
 }
@@ -100,15 +230,17 @@ pub struct OffsetVec2d<T> {
     pub data: Vec2d<T>,
 }
 
-impl<T: Copy> OffsetVec2d<T> {
+impl<T: Clone> OffsetVec2d<T> {
     pub fn new(offset: Offset, width: usize, height: usize, init: T) -> OffsetVec2d<T> {
         OffsetVec2d {
             offset,
             data: Vec2d::new(width, height, init),
         }
     }
+}
 
-    pub fn get(&self, index: Point) -> Option<T> {
+impl<T> OffsetVec2d<T> {
+    pub fn get(&self, index: Point) -> Option<&T> {
         self.data.get(index - self.offset)
     }
 }
@@ -126,18 +258,128 @@ impl<T> IndexMut<Point> for OffsetVec2d<T> {
     }
 }
 
+/// Grid without fixed bounds, for simulations that don't know how far they
+/// will spread. Writing outside of the stored area grows it in that direction
+/// and points never written hold the default value.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct GrowingVec2d<T> {
+    default: T,
+    offset: Offset,
+    data: Vec2d<T>,
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T: Clone> GrowingVec2d<T> {
+    pub fn new(default: T) -> GrowingVec2d<T> {
+        GrowingVec2d {
+            data: Vec2d::new(0, 0, default.clone()),
+            default,
+            offset: Offset::ZERO,
+            bounds: None,
+        }
+    }
+
+    /// Value at `index` if it lies within the bounds, whether it was written
+    /// or not.
+    pub fn get(&self, index: Point) -> Option<&T> {
+        let (min, max) = self.bounds?;
+        let inside = (0..2).all(|i| (min.0[i]..=max.0[i]).contains(&index.0[i]));
+        inside.then(|| &self[index])
+    }
+
+    pub fn set(&mut self, index: Point, value: T) {
+        self[index] = value;
+    }
+
+    /// Smallest and largest coordinates written so far, both included.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    /// Copy of the area within the bounds.
+    pub fn cropped(&self) -> Vec2d<T> {
+        let Some((min, max)) = self.bounds else {
+            return Vec2d::new(0, 0, self.default.clone());
+        };
+        let size = max - min;
+        let mut grid = Vec2d::new(
+            size.x() as usize + 1,
+            size.y() as usize + 1,
+            self.default.clone(),
+        );
+        for point in grid.points() {
+            grid[point] = self[Point::new(min.x() + point.x(), min.y() + point.y())].clone();
+        }
+        grid
+    }
+
+    /// Makes room for `index`, at least doubling the size in every direction
+    /// that has to grow so that growing point by point stays cheap.
+    fn grow_to(&mut self, index: Point) {
+        if self.data.contains(index - self.offset) {
+            return;
+        }
+        let (width, height) = (self.data.width as i32, self.data.height as i32);
+        let (mut left, mut top) = (self.offset.x(), self.offset.y());
+        let (mut right, mut bottom) = (left + width, top + height);
+        if width == 0 {
+            (left, top) = (index.x(), index.y());
+            (right, bottom) = (left + 1, top + 1);
+        }
+        if index.x() < left {
+            left = index.x().min(left - width);
+        } else if index.x() >= right {
+            right = (index.x() + 1).max(right + width);
+        }
+        if index.y() < top {
+            top = index.y().min(top - height);
+        } else if index.y() >= bottom {
+            bottom = (index.y() + 1).max(bottom + height);
+        }
+        let offset = Offset::new(left, top);
+        let size = ((right - left) as usize, (bottom - top) as usize);
+        let mut data = Vec2d::new(size.0, size.1, self.default.clone());
+        for (point, value) in self.data.iter() {
+            data[point + self.offset - offset] = value.clone();
+        }
+        self.data = data;
+        self.offset = offset;
+    }
+}
+
+impl<T: Clone> Index<Point> for GrowingVec2d<T> {
+    type Output = T;
+    fn index(&self, index: Point) -> &Self::Output {
+        self.data.get(index - self.offset).unwrap_or(&self.default)
+    }
+}
+
+impl<T: Clone> IndexMut<Point> for GrowingVec2d<T> {
+    fn index_mut(&mut self, index: Point) -> &mut Self::Output {
+        self.grow_to(index);
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (
+                Point::new(min.x().min(index.x()), min.y().min(index.y())),
+                Point::new(max.x().max(index.x()), max.y().max(index.y())),
+            ),
+            None => (index, index),
+        });
+        &mut self.data[index - self.offset]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn can_add_offset_to_point() {
-        let point = Point(5, 5);
+        let point = Point::new(5, 5);
         let tests = vec![
-            (Offset(1, 1), Point(6, 6)),
-            (Offset(-1, -1), Point(4, 4)),
-            (Offset(0, 1), Point(5, 6)),
-            (Offset(-1, 1), Point(4, 6)),
+            (Offset::new(1, 1), Point::new(6, 6)),
+            (Offset::new(-1, -1), Point::new(4, 4)),
+            (Offset::new(0, 1), Point::new(5, 6)),
+            (Offset::new(-1, 1), Point::new(4, 6)),
         ];
         for (offset, want) in tests {
             assert_eq!(point + offset, want);
@@ -146,24 +388,167 @@ mod tests {
 
     #[test]
     fn can_subtract_offset_to_point() {
-        let point = Point(5, 5);
+        let point = Point::new(5, 5);
         let tests = vec![
-            (Offset(1, 1), Point(4, 4)),
-            (Offset(-1, -1), Point(6, 6)),
-            (Offset(0, 1), Point(5, 4)),
-            (Offset(-1, 1), Point(6, 4)),
+            (Offset::new(1, 1), Point::new(4, 4)),
+            (Offset::new(-1, -1), Point::new(6, 6)),
+            (Offset::new(0, 1), Point::new(5, 4)),
+            (Offset::new(-1, 1), Point::new(6, 4)),
         ];
         for (offset, want) in tests {
             assert_eq!(point - offset, want);
+            assert_eq!(point - want, offset);
         }
     }
 
     #[test]
     fn can_store_data() {
         let mut vec = Vec2d::new(10, 10, false);
-        let index = Point(5, 5);
+        let index = Point::new(5, 5);
         vec[index] = true;
-        assert_eq!(vec[index], true);
-        assert_eq!(vec.get(index), Some(true));
+        assert!(vec[index]);
+        assert_eq!(vec.get(index), Some(&true));
+    }
+
+    #[test]
+    fn checks_bounds() {
+        let mut vec = Vec2d::new(3, 2, 0);
+        for point in [
+            Point::new(-1, 0),
+            Point::new(0, -1),
+            Point::new(3, 0),
+            Point::new(0, 2),
+        ] {
+            assert!(!vec.contains(point));
+            assert_eq!(vec.get(point), None);
+            assert!(!vec.set(point, 1));
+        }
+        assert!(vec.set(Point::new(2, 1), 5));
+        assert_eq!(vec.data, [0, 0, 0, 0, 0, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "inside the grid")]
+    fn index_panics_past_row_end() {
+        let vec = Vec2d::new(3, 2, 0);
+        let _ = vec[Point::new(3, 0)];
+    }
+
+    #[test]
+    #[should_panic(expected = "inside the grid")]
+    fn index_mut_panics_for_negative_points() {
+        let mut vec = Vec2d::new(3, 2, 0);
+        vec[Point::new(-1, 1)] = 1;
+    }
+
+    #[test]
+    fn can_grow_in_all_directions() {
+        let mut grid = GrowingVec2d::new('.');
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid[Point::new(3, 3)], '.');
+        assert_eq!(grid.get(Point::new(3, 3)), None);
+        grid[Point::new(3, 3)] = 'a';
+        grid.set(Point::new(-2, 4), 'b');
+        grid[Point::new(5, -1)] = 'c';
+        assert_eq!(grid[Point::new(3, 3)], 'a');
+        assert_eq!(grid.get(Point::new(-2, 4)), Some(&'b'));
+        assert_eq!(grid[Point::new(5, -1)], 'c');
+        assert_eq!(grid[Point::new(100, 100)], '.');
+        assert_eq!(grid.get(Point::new(100, 100)), None);
+        assert_eq!(grid.get(Point::new(-1, 3)), Some(&'.'));
+        assert_eq!(grid.get(Point::new(-3, 3)), None);
+        assert_eq!(grid.get(Point::new(-2, 5)), None);
+        let bounds = (Point::new(-2, -1), Point::new(5, 4));
+        assert_eq!(grid.bounds(), Some(bounds));
+        let have = grid.cropped();
+        assert_eq!((have.width, have.height), (8, 6));
+        assert_eq!(have[Point::new(5, 4)], 'a');
+        assert_eq!(have[Point::new(0, 5)], 'b');
+        assert_eq!(have[Point::new(7, 0)], 'c');
+        assert_eq!(have.data.iter().filter(|&&c| c == '.').count(), 45);
+    }
+
+    #[test]
+    fn grows_storage_by_doubling() {
+        let mut grid = GrowingVec2d::new(0);
+        for x in 0..1000 {
+            grid[Point::new(-x, x / 2)] = x;
+        }
+        assert!(grid.data.width < 2048 && grid.data.height < 1024);
+        assert!(grid.data.height > 500);
+        assert_eq!(grid[Point::new(0, 500)], 0);
+        assert_eq!(grid.get(Point::new(0, 500)), None);
+        assert_eq!(grid[Point::new(-999, 499)], 999);
+        assert_eq!(
+            grid.bounds(),
+            Some((Point::new(-999, 0), Point::new(0, 499)))
+        );
+    }
+
+    #[test]
+    fn can_iterate_rows_and_columns() {
+        let vec = Vec2d {
+            width: 3,
+            height: 2,
+            data: vec![1, 2, 3, 4, 5, 6],
+        };
+        assert_eq!(vec.row(1).copied().collect::<Vec<_>>(), [4, 5, 6]);
+        assert_eq!(vec.row(0).rev().copied().collect::<Vec<_>>(), [3, 2, 1]);
+        assert_eq!(vec.col(2).copied().collect::<Vec<_>>(), [3, 6]);
+        assert_eq!(vec.col(0).rev().copied().collect::<Vec<_>>(), [4, 1]);
+        assert_eq!(vec.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+        let (point, value) = vec.iter().nth(4).unwrap();
+        assert_eq!((point, *value), (Point::new(1, 1), 5));
+    }
+
+    #[test]
+    fn can_parse_grid() {
+        let digit = |c: char| c.to_digit(10).ok_or("Not a digit");
+        let have = Vec2d::from_str_grid("123\n456\n", digit).unwrap();
+        assert_eq!(
+            have,
+            Vec2d {
+                width: 3,
+                height: 2,
+                data: vec![1, 2, 3, 4, 5, 6],
+            }
+        );
+        let error = Vec2d::from_str_grid("123\n4x6", digit).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(2)));
+        let error = Vec2d::from_str_grid("123\n45\n789", digit).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+        let error = Vec2d::from_str_grid("123\n4567", digit).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(4)));
+        assert!(Vec2d::from_str_grid("", digit).is_err());
+    }
+
+    #[test]
+    fn can_find_markers() {
+        let map = Vec2d::from_str_grid("S..\n.E.", Ok::<_, Error>).unwrap();
+        assert_eq!(map.find_unique(&'S'), Ok(Point::new(0, 0)));
+        assert_eq!(map.find_unique(&'E'), Ok(Point::new(1, 1)));
+        assert!(map.find_unique(&'X').is_err());
+        let error = map.find_unique(&'.').unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), Some(3)));
+        assert_eq!(
+            map.map(|&c| c == '.').data,
+            [false, true, true, true, false, true]
+        );
+    }
+
+    #[test]
+    fn can_find_neighbors() {
+        let vec = Vec2d::new(3, 2, 0);
+        let neighbors: Vec<Point> = vec.neighbors(Point::new(0, 0)).collect();
+        assert_eq!(neighbors, [Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(vec.neighbors(Point::new(1, 1)).count(), 3);
+        let adjacent: Vec<Point> = vec.adjacent(Point::new(0, 0)).collect();
+        assert_eq!(
+            adjacent,
+            [Point::new(1, 0), Point::new(0, 1), Point::new(1, 1)]
+        );
+        assert_eq!(vec.adjacent(Point::new(1, 1)).count(), 5);
+        assert!(Offset::orthogonal().eq(Offset::ORTHOGONAL));
+        assert!(Offset::adjacent().eq(Offset::ADJACENT));
     }
 }